@@ -0,0 +1,32 @@
+use clap::Parser;
+use proxyrs::configuration::Settings;
+use proxyrs::providers::fixtures::record;
+use proxyrs::providers::registry::ProviderRegistry;
+
+/// Save what every configured provider returns today, for parser tests and
+/// `ClientMode::Replay` runs.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directory to store the responses in
+    #[arg(long, default_value = "fixtures")]
+    dir: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let settings = Settings::new().unwrap_or_else(|e| {
+        println!("Warning: Failed to load config.toml: {}. Using default providers.", e);
+        Settings::default()
+    });
+
+    for (name, result) in record(&settings, &ProviderRegistry::new(), &args.dir).await {
+        match result {
+            Ok(count) => println!("{}: {} proxies", name, count),
+            Err(e) => println!("{}: failed: {}", name, e),
+        }
+    }
+    println!("Responses saved under {}/", args.dir);
+}
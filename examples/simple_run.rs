@@ -1,20 +1,17 @@
-use clap::{Parser, ValueEnum};
+use clap::Parser;
 use proxyrs::ProxyGenerator;
 use proxyrs::filter::ProxyFilter;
-use proxyrs::providers::cool_proxy::CoolProxy;
-use proxyrs::providers::free_proxy_list::FreeProxyList;
-use proxyrs::providers::cybersyndrome::Cybersyndrome;
-use proxyrs::providers::proxyscrape::ProxyScrape;
 use proxyrs::configuration::Settings;
+use proxyrs::providers::fixtures::ClientMode;
 use proxyrs::proxy::ProxyType;
 use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Proxy types to include (e.g. http, https)
-    #[arg(long, value_enum)]
-    type_: Option<Vec<ProxyTypeArg>>,
+    /// Proxy types to include (http, https, socks4, socks4a, socks5, socks5h)
+    #[arg(long)]
+    type_: Option<Vec<ProxyType>>,
 
     /// Countries to include (e.g. US, JP)
     #[arg(long)]
@@ -35,25 +32,10 @@ struct Args {
     /// Number of unique proxies to find
     #[arg(long, default_value_t = 1)]
     limit: usize,
-}
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
-enum ProxyTypeArg {
-    Http,
-    Https,
-    Socks4,
-    Socks5,
-}
-
-impl From<ProxyTypeArg> for ProxyType {
-    fn from(arg: ProxyTypeArg) -> Self {
-        match arg {
-            ProxyTypeArg::Http => ProxyType::Http,
-            ProxyTypeArg::Https => ProxyType::Https,
-            ProxyTypeArg::Socks4 => ProxyType::Socks4,
-            ProxyTypeArg::Socks5 => ProxyType::Socks5,
-        }
-    }
+    /// Serve provider pages from a directory written by `record_fixtures`
+    #[arg(long)]
+    replay: Option<String>,
 }
 
 #[tokio::main]
@@ -62,46 +44,14 @@ async fn main() {
 
     // env_logger::builder().filter_level(log::LevelFilter::Info).init();
 
-    let mut generator = ProxyGenerator::new();
-    
     // Load configuration
-    match Settings::new() {
-        Ok(settings) => {
-             // Cybersyndrome
-            if let Some(conf) = settings.providers.cybersyndrome {
-                if conf.enabled {
-                    generator.add_provider(Cybersyndrome::new());
-                }
-            }
-
-            // ProxyScrape
-            if let Some(conf) = settings.providers.proxyscrape {
-                if conf.enabled {
-                    generator.add_provider(ProxyScrape::new());
-                }
-            }
-
-            // CoolProxy
-            if let Some(conf) = settings.providers.cool_proxy {
-                 if conf.enabled {
-                    generator.add_provider(CoolProxy::new());
-                }
-            }
-
-            // FreeProxyList
-            if let Some(conf) = settings.providers.free_proxy_list {
-                 if conf.enabled {
-                    generator.add_provider(FreeProxyList::new());
-                }
-            }
-        },
-        Err(e) => {
-             println!("Warning: Failed to load config.toml: {}. Using default providers.", e);
-             generator.add_provider(Cybersyndrome::new());
-             generator.add_provider(ProxyScrape::new());
-             generator.add_provider(CoolProxy::new());
-             generator.add_provider(FreeProxyList::new());
-        }
+    let settings = Settings::new().unwrap_or_else(|e| {
+        println!("Warning: Failed to load config.toml: {}. Using default providers.", e);
+        Settings::default()
+    });
+    let generator = ProxyGenerator::from_settings(&settings);
+    if let Some(dir) = &args.replay {
+        generator.set_client_mode(ClientMode::Replay(dir.into())).await;
     }
 
     // Build filter from args
@@ -109,7 +59,7 @@ async fn main() {
         .with_max_latency(Duration::from_millis(args.max_latency_ms));
 
     if let Some(types) = args.type_ {
-        filter = filter.with_proxy_types(types.into_iter().map(Into::into).collect());
+        filter = filter.with_proxy_types(types);
     }
 
     if let Some(countries) = args.country {
@@ -143,7 +93,7 @@ async fn main() {
         }
     };
 
-    if let Err(_) = tokio::time::timeout(Duration::from_secs(args.timeout_s), search_future).await {
+    if tokio::time::timeout(Duration::from_secs(args.timeout_s), search_future).await.is_err() {
         println!("Timeout reached! Could not find enough proxies matching your criteria within {} seconds.", args.timeout_s);
     }
 }
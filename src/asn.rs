@@ -0,0 +1,143 @@
+use crate::proxy::ProxyMetadata;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Rough kind of network an address belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkClass {
+    Hosting,
+    Residential,
+    Mobile,
+    Unknown,
+}
+
+impl fmt::Display for NetworkClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkClass::Hosting => write!(f, "hosting"),
+            NetworkClass::Residential => write!(f, "residential"),
+            NetworkClass::Mobile => write!(f, "mobile"),
+            NetworkClass::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsnInfo {
+    pub number: u32,
+    pub org: String,
+    pub class: NetworkClass,
+}
+
+// Matched against the lowercased AS description
+const HOSTING_KEYWORDS: &[&str] = &[
+    "hosting", "cloud", "datacenter", "data center", "data-center", "server", "colocation",
+    "vps", "dedicated", "amazon", "aws", "google", "microsoft", "azure", "digitalocean",
+    "ovh", "hetzner", "linode", "akamai", "vultr", "choopa", "leaseweb", "contabo", "m247",
+    "alibaba", "tencent", "oracle", "scaleway", "online s.a.s", "psychz", "quadranet",
+];
+const MOBILE_KEYWORDS: &[&str] = &[
+    "mobile", "wireless", "cellular", "gsm", "moviles", "mobil", "cellco",
+];
+
+/// Offline IP-to-ASN lookup with a keyword-based hosting/residential/mobile guess.
+pub struct AsnDb {
+    ranges: Vec<(IpAddr, IpAddr, u32, String)>, // Sorted by range start
+    overrides: HashMap<u32, NetworkClass>,
+}
+
+impl AsnDb {
+    /// Load an iptoasn.com style dataset: `range_start range_end AS_number
+    /// country_code AS_description`, separated by tabs or commas. Ranges with
+    /// AS number 0 ("Not routed") are skipped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let mut ranges = Vec::new();
+
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let sep = if line.contains('\t') { '\t' } else { ',' };
+            let cols: Vec<&str> = line.splitn(5, sep).map(str::trim).collect();
+            if cols.len() < 3 {
+                continue;
+            }
+
+            let (Ok(start), Ok(end), Ok(number)) = (
+                cols[0].parse::<IpAddr>(),
+                cols[1].parse::<IpAddr>(),
+                cols[2].trim_start_matches("AS").parse::<u32>(),
+            ) else {
+                continue;
+            };
+            if number == 0 {
+                continue;
+            }
+            let org = cols.get(4).map(|s| s.trim_matches('"').to_string()).unwrap_or_default();
+            ranges.push((start, end, number, org));
+        }
+
+        if ranges.is_empty() {
+            return Err(anyhow!("no ASN ranges found"));
+        }
+
+        ranges.sort_by_key(|(start, ..)| *start);
+        Ok(Self {
+            ranges,
+            overrides: HashMap::new(),
+        })
+    }
+
+    /// Force the class of an AS whose description the keyword guess gets wrong.
+    pub fn with_class(mut self, asn: u32, class: NetworkClass) -> Self {
+        self.overrides.insert(asn, class);
+        self
+    }
+
+    pub fn lookup(&self, ip: IpAddr) -> Option<AsnInfo> {
+        let idx = self.ranges.partition_point(|(start, ..)| *start <= ip);
+        let (_, end, number, org) = self.ranges.get(idx.checked_sub(1)?)?;
+        if ip > *end {
+            return None;
+        }
+
+        let class = self
+            .overrides
+            .get(number)
+            .copied()
+            .unwrap_or_else(|| classify(org));
+        Some(AsnInfo {
+            number: *number,
+            org: org.clone(),
+            class,
+        })
+    }
+
+    pub fn enrich(&self, meta: &mut ProxyMetadata) {
+        if let Some(info) = meta.ip().and_then(|ip| self.lookup(ip)) {
+            meta.asn = Some(info);
+        }
+    }
+}
+
+fn classify(org: &str) -> NetworkClass {
+    if org.is_empty() {
+        return NetworkClass::Unknown;
+    }
+    let org = org.to_lowercase();
+    if MOBILE_KEYWORDS.iter().any(|k| org.contains(k)) {
+        NetworkClass::Mobile
+    } else if HOSTING_KEYWORDS.iter().any(|k| org.contains(k)) {
+        NetworkClass::Hosting
+    } else {
+        NetworkClass::Residential
+    }
+}
@@ -1,47 +1,185 @@
-use serde::Deserialize;
+use crate::proxy::ProxyType;
+use crate::providers::base::ProviderOptions;
+use crate::providers::cybersyndrome::CybersyndromeList;
+use crate::providers::list::{CsvColumns, JsonFields, ListFormat};
+use crate::providers::scrape::ScrapeConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::time::Duration;
 use anyhow::Result;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Settings {
     pub providers: Providers,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Providers {
     pub cool_proxy: Option<ProviderConfig>,
     pub free_proxy_list: Option<ProviderConfig>,
+    pub sslproxies: Option<ProviderConfig>, // Sister sites of free-proxy-list.net
+    pub us_proxy: Option<ProviderConfig>,
+    pub socks_proxy: Option<ProviderConfig>,
     pub cybersyndrome: Option<CybersyndromeConfig>,
     pub proxyscrape: Option<ProviderConfig>,
+    #[serde(default)]
+    pub file: Vec<FileConfig>, // `[[providers.file]]` entries
+    #[serde(default)]
+    pub scrape: Vec<ScrapeConfig>, // `[[providers.scrape]]` entries
+    #[serde(default)]
+    pub url_list: Vec<UrlListConfig>, // `[[providers.url_list]]` entries
+    #[serde(flatten)]
+    pub other: toml::Table, // Sections for providers registered at runtime
 }
 
-#[derive(Debug, Deserialize)]
+impl Providers {
+    /// Every section as raw TOML keyed by provider name, as `ProviderRegistry`
+    /// consumes them.
+    pub fn sections(&self) -> Result<toml::Table> {
+        Ok(toml::Table::try_from(self)?)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ProviderConfig {
     pub enabled: bool,
     pub url: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub ttl_secs: Option<u64>,
+    pub proxy: Option<String>, // Fixed upstream proxy URL for fetching the list
+}
+
+impl ProviderConfig {
+    fn enabled() -> Self {
+        Self {
+            enabled: true,
+            url: None,
+            headers: HashMap::new(),
+            ttl_secs: None,
+            proxy: None,
+        }
+    }
+
+    pub fn options(&self) -> Result<ProviderOptions> {
+        provider_options(self.url.as_deref(), &self.headers, self.ttl_secs, self.proxy.as_deref())
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CybersyndromeConfig {
+    pub enabled: bool,
+    pub url: Option<String>, // Site or mirror the lists are fetched from
+    #[serde(default)]
+    pub lists: Vec<CybersyndromeList>, // Empty for ranking, anonymous and newest
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub ttl_secs: Option<u64>,
+    pub proxy: Option<String>,
+}
+
+impl CybersyndromeConfig {
+    pub fn options(&self) -> Result<ProviderOptions> {
+        provider_options(self.url.as_deref(), &self.headers, self.ttl_secs, self.proxy.as_deref())
+    }
+}
+
+fn provider_options(
+    url: Option<&str>,
+    headers: &HashMap<String, String>,
+    ttl_secs: Option<u64>,
+    proxy: Option<&str>,
+) -> Result<ProviderOptions> {
+    let mut options = ProviderOptions::new();
+    if let Some(url) = url {
+        options = options.with_url(url);
+    }
+    for (name, value) in headers {
+        options = options.with_header(name, value);
+    }
+    if let Some(secs) = ttl_secs {
+        options = options.with_ttl(Duration::from_secs(secs));
+    }
+    if let Some(proxy) = proxy {
+        options = options.with_upstream(proxy)?;
+    }
+    Ok(options)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileConfig {
+    pub enabled: bool,
+    pub path: String,
+    #[serde(default)]
+    pub format: ListFormat,
+    pub kind: Option<ProxyType>, // For entries that don't state a type
+    #[serde(default)]
+    pub columns: CsvColumns,
+    #[serde(default)]
+    pub fields: JsonFields,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UrlListConfig {
     pub enabled: bool,
     pub url: String,
+    pub name: Option<String>, // Defaults to the URL's host
+    #[serde(default)]
+    pub format: ListFormat,
+    pub kind: Option<ProxyType>, // For entries that don't state a type
+    pub country: Option<String>, // For entries that don't state a country
+    #[serde(default)]
+    pub columns: CsvColumns,
+    #[serde(default)]
+    pub fields: JsonFields,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub ttl_secs: Option<u64>,
+    pub proxy: Option<String>, // Fixed upstream proxy URL for fetching the list
+}
+
+impl UrlListConfig {
+    pub fn options(&self) -> Result<ProviderOptions> {
+        provider_options(None, &self.headers, self.ttl_secs, self.proxy.as_deref())
+    }
+}
+
+// Every built-in list provider, enabled with its default URL
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            providers: Providers {
+                cool_proxy: Some(ProviderConfig::enabled()),
+                free_proxy_list: Some(ProviderConfig::enabled()),
+                sslproxies: Some(ProviderConfig::enabled()),
+                us_proxy: Some(ProviderConfig::enabled()),
+                socks_proxy: Some(ProviderConfig::enabled()),
+                cybersyndrome: Some(CybersyndromeConfig {
+                    enabled: true,
+                    url: None,
+                    lists: Vec::new(),
+                    headers: HashMap::new(),
+                    ttl_secs: None,
+                    proxy: None,
+                }),
+                proxyscrape: Some(ProviderConfig::enabled()),
+                file: Vec::new(),
+                scrape: Vec::new(),
+                url_list: Vec::new(),
+                other: toml::Table::new(),
+            },
+        }
+    }
 }
 
 impl Settings {
     pub fn new() -> Result<Self> {
         let config_data = fs::read_to_string("config.toml").unwrap_or_else(|_| "".to_string());
         
-        // If config file is missing or empty, use default values (mocked by deserializing partial or using defaults if I implied Default trait, but here I'll just check existence)
-        // Actually, let's just default if missing.
+        // If config file is missing or empty, use default values
         if config_data.is_empty() {
-             return Ok(Settings {
-                 providers: Providers {
-                     cool_proxy: Some(ProviderConfig { enabled: true, url: None }),
-                     free_proxy_list: Some(ProviderConfig { enabled: true, url: None }),
-                     cybersyndrome: Some(CybersyndromeConfig { enabled: true, url: "https://www.cybersyndrome.net/plr6.html".to_string() }),
-                     proxyscrape: Some(ProviderConfig { enabled: true, url: None }),
-                 }
-             });
+            return Ok(Settings::default());
         }
 
         let settings: Settings = toml::from_str(&config_data)?;
@@ -0,0 +1,566 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// ISO 3166-1 country, normalised from whatever form a provider reports
+/// (alpha-2, alpha-3 or English name).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Country(Option<&'static str>); // Alpha-2 code, None when unknown
+
+impl Country {
+    pub const UNKNOWN: Country = Country(None);
+
+    /// Parse a code or name, falling back to `Country::UNKNOWN`.
+    pub fn parse(s: &str) -> Self {
+        lookup(s).map(|(alpha2, _, _)| Country(Some(*alpha2))).unwrap_or_default()
+    }
+
+    pub fn is_known(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// ISO alpha-2 code, e.g. "US".
+    pub fn code(&self) -> Option<&'static str> {
+        self.0
+    }
+
+    /// ISO alpha-3 code, e.g. "USA".
+    pub fn alpha3(&self) -> Option<&'static str> {
+        self.entry().map(|(_, alpha3, _)| *alpha3)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        self.entry().map(|(_, _, name)| *name)
+    }
+
+    fn entry(&self) -> Option<&'static (&'static str, &'static str, &'static str)> {
+        let code = self.0?;
+        COUNTRIES.iter().find(|(alpha2, _, _)| *alpha2 == code)
+    }
+}
+
+impl FromStr for Country {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Country::parse(s) {
+            Country(None) => Err(anyhow!("unknown country: {}", s)),
+            country => Ok(country),
+        }
+    }
+}
+
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.unwrap_or("Unknown"))
+    }
+}
+
+// Serialized as the alpha-2 code, or null when unknown
+impl Serialize for Country {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Country {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = Option::<String>::deserialize(deserializer)?;
+        Ok(s.map(|s| Country::parse(&s)).unwrap_or_default())
+    }
+}
+
+/// Group of countries usable in include/exclude lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Region {
+    EuropeanUnion,
+    Europe,
+    Asia,
+    MiddleEast,
+    Africa,
+    NorthAmerica,
+    SouthAmerica,
+    LatinAmerica,
+    Oceania,
+}
+
+impl Region {
+    pub fn parse(s: &str) -> Option<Self> {
+        let region = match normalize(s).as_str() {
+            "eu" | "european union" => Region::EuropeanUnion,
+            "europe" => Region::Europe,
+            "asia" => Region::Asia,
+            "middle east" | "mideast" => Region::MiddleEast,
+            "africa" => Region::Africa,
+            "north america" => Region::NorthAmerica,
+            "south america" => Region::SouthAmerica,
+            "latam" | "latin america" => Region::LatinAmerica,
+            "oceania" => Region::Oceania,
+            _ => return None,
+        };
+        Some(region)
+    }
+
+    pub fn members(&self) -> &'static [&'static str] {
+        match self {
+            Region::EuropeanUnion => EU,
+            Region::Europe => EUROPE,
+            Region::Asia => ASIA,
+            Region::MiddleEast => MIDDLE_EAST,
+            Region::Africa => AFRICA,
+            Region::NorthAmerica => NORTH_AMERICA,
+            Region::SouthAmerica => SOUTH_AMERICA,
+            Region::LatinAmerica => LATIN_AMERICA,
+            Region::Oceania => OCEANIA,
+        }
+    }
+
+    pub fn contains(&self, country: Country) -> bool {
+        // The EU is also part of Europe
+        let members = self.members();
+        match (self, country.code()) {
+            (_, None) => false,
+            (Region::Europe, Some(code)) => members.contains(&code) || EU.contains(&code),
+            (_, Some(code)) => members.contains(&code),
+        }
+    }
+}
+
+/// Entry of a country include/exclude list: a single country or a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CountrySelector {
+    Country(Country),
+    Region(Region),
+}
+
+impl CountrySelector {
+    pub fn matches(&self, country: Country) -> bool {
+        match self {
+            CountrySelector::Country(c) => c.is_known() && *c == country,
+            CountrySelector::Region(r) => r.contains(country),
+        }
+    }
+}
+
+impl FromStr for CountrySelector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Regions first: "EU" is not a country code, while e.g. "NA" (Namibia) is
+        if let Some(region) = Region::parse(s) {
+            return Ok(CountrySelector::Region(region));
+        }
+        s.parse().map(CountrySelector::Country)
+    }
+}
+
+fn lookup(s: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    let s = s.trim();
+    match s.len() {
+        2 => {
+            let code = if s.eq_ignore_ascii_case("UK") { "GB" } else { s };
+            if let Some(entry) = COUNTRIES.iter().find(|(alpha2, _, _)| alpha2.eq_ignore_ascii_case(code)) {
+                return Some(entry);
+            }
+        }
+        3 => {
+            if let Some(entry) = COUNTRIES.iter().find(|(_, alpha3, _)| alpha3.eq_ignore_ascii_case(s)) {
+                return Some(entry);
+            }
+        }
+        _ => {}
+    }
+
+    let name = normalize(s);
+    let alpha2 = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, alpha2)| *alpha2);
+    COUNTRIES.iter().find(|(code, _, full)| Some(*code) == alpha2 || normalize(full) == name)
+}
+
+// Lowercase, fold the few accents that show up in names, and reduce punctuation to
+// single spaces so "Korea, Republic of" and "korea republic of" compare equal.
+fn normalize(s: &str) -> String {
+    let folded: String = s
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ä' | 'å' | 'Å' | 'Á' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'î' => 'i',
+            'ó' | 'ô' | 'ö' => 'o',
+            'ú' | 'ü' => 'u',
+            'ç' => 'c',
+            '&' => ' ',
+            c if c.is_alphanumeric() => c.to_ascii_lowercase(),
+            _ => ' ',
+        })
+        .collect();
+
+    let words: Vec<&str> = folded
+        .split_whitespace()
+        .filter(|w| *w != "the" && *w != "and")
+        .collect();
+    words.join(" ")
+}
+
+// Alternative names, already normalised
+const ALIASES: &[(&str, &str)] = &[
+    ("usa", "US"),
+    ("us", "US"),
+    ("america", "US"),
+    ("united states of america", "US"),
+    ("uk", "GB"),
+    ("great britain", "GB"),
+    ("britain", "GB"),
+    ("england", "GB"),
+    ("russian federation", "RU"),
+    ("korea", "KR"),
+    ("korea republic of", "KR"),
+    ("republic of korea", "KR"),
+    ("korea south", "KR"),
+    ("korea democratic people s republic of", "KP"),
+    ("korea north", "KP"),
+    ("viet nam", "VN"),
+    ("czech republic", "CZ"),
+    ("iran islamic republic of", "IR"),
+    ("taiwan province of china", "TW"),
+    ("taiwan republic of china", "TW"),
+    ("hong kong sar", "HK"),
+    ("hong kong sar china", "HK"),
+    ("macau", "MO"),
+    ("holland", "NL"),
+    ("turkiye", "TR"),
+    ("ivory coast", "CI"),
+    ("burma", "MM"),
+    ("swaziland", "SZ"),
+    ("cabo verde", "CV"),
+    ("east timor", "TL"),
+    ("bolivia plurinational state of", "BO"),
+    ("venezuela bolivarian republic of", "VE"),
+    ("moldova republic of", "MD"),
+    ("republic of moldova", "MD"),
+    ("tanzania united republic of", "TZ"),
+    ("syrian arab republic", "SY"),
+    ("lao people s democratic republic", "LA"),
+    ("palestine state of", "PS"),
+    ("palestinian territory", "PS"),
+    ("brunei darussalam", "BN"),
+    ("macedonia", "MK"),
+    ("republic of north macedonia", "MK"),
+    ("congo democratic republic of", "CD"),
+    ("democratic republic of congo", "CD"),
+    ("congo kinshasa", "CD"),
+    ("congo brazzaville", "CG"),
+    ("republic of congo", "CG"),
+    ("uae", "AE"),
+    ("vatican", "VA"),
+    ("holy see", "VA"),
+    ("micronesia federated states of", "FM"),
+    ("libyan arab jamahiriya", "LY"),
+];
+
+const EU: &[&str] = &[
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GR", "HR", "HU", "IE",
+    "IT", "LT", "LU", "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK",
+];
+
+// Non-EU part of Europe; `Region::Europe` adds the EU members
+const EUROPE: &[&str] = &[
+    "AD", "AL", "AX", "BA", "BY", "CH", "FO", "GB", "GG", "GI", "IM", "IS", "JE", "LI", "MC",
+    "MD", "ME", "MK", "NO", "RS", "RU", "SJ", "SM", "UA", "VA",
+];
+
+const ASIA: &[&str] = &[
+    "AE", "AF", "AM", "AZ", "BD", "BH", "BN", "BT", "CN", "GE", "HK", "ID", "IL", "IN", "IQ",
+    "IR", "JO", "JP", "KG", "KH", "KP", "KR", "KW", "KZ", "LA", "LB", "LK", "MM", "MN", "MO",
+    "MV", "MY", "NP", "OM", "PH", "PK", "PS", "QA", "SA", "SG", "SY", "TH", "TJ", "TL", "TM",
+    "TR", "TW", "UZ", "VN", "YE",
+];
+
+const MIDDLE_EAST: &[&str] = &[
+    "AE", "BH", "CY", "EG", "IL", "IQ", "IR", "JO", "KW", "LB", "OM", "PS", "QA", "SA", "SY",
+    "TR", "YE",
+];
+
+const AFRICA: &[&str] = &[
+    "AO", "BF", "BI", "BJ", "BW", "CD", "CF", "CG", "CI", "CM", "CV", "DJ", "DZ", "EG", "EH",
+    "ER", "ET", "GA", "GH", "GM", "GN", "GQ", "GW", "KE", "KM", "LR", "LS", "LY", "MA", "MG",
+    "ML", "MR", "MU", "MW", "MZ", "NA", "NE", "NG", "RE", "RW", "SC", "SD", "SH", "SL", "SN",
+    "SO", "SS", "ST", "SZ", "TD", "TG", "TN", "TZ", "UG", "YT", "ZA", "ZM", "ZW",
+];
+
+// Including Central America and the Caribbean
+const NORTH_AMERICA: &[&str] = &[
+    "AG", "AI", "AW", "BB", "BL", "BM", "BQ", "BS", "BZ", "CA", "CR", "CU", "CW", "DM", "DO",
+    "GD", "GL", "GP", "GT", "HN", "HT", "JM", "KN", "KY", "LC", "MF", "MQ", "MS", "MX", "NI",
+    "PA", "PM", "PR", "SV", "SX", "TC", "TT", "US", "VC", "VG", "VI",
+];
+
+const SOUTH_AMERICA: &[&str] = &[
+    "AR", "BO", "BR", "CL", "CO", "EC", "FK", "GF", "GY", "PE", "PY", "SR", "UY", "VE",
+];
+
+const LATIN_AMERICA: &[&str] = &[
+    "AR", "BO", "BR", "BZ", "CL", "CO", "CR", "CU", "DO", "EC", "GF", "GT", "GY", "HN", "HT",
+    "MX", "NI", "PA", "PE", "PR", "PY", "SR", "SV", "UY", "VE",
+];
+
+const OCEANIA: &[&str] = &[
+    "AS", "AU", "CK", "FJ", "FM", "GU", "KI", "MH", "MP", "NC", "NF", "NR", "NU", "NZ", "PF",
+    "PG", "PN", "PW", "SB", "TK", "TO", "TV", "VU", "WF", "WS",
+];
+
+// (alpha-2, alpha-3, English short name)
+const COUNTRIES: &[(&str, &str, &str)] = &[
+    ("AD", "AND", "Andorra"),
+    ("AE", "ARE", "United Arab Emirates"),
+    ("AF", "AFG", "Afghanistan"),
+    ("AG", "ATG", "Antigua and Barbuda"),
+    ("AI", "AIA", "Anguilla"),
+    ("AL", "ALB", "Albania"),
+    ("AM", "ARM", "Armenia"),
+    ("AO", "AGO", "Angola"),
+    ("AQ", "ATA", "Antarctica"),
+    ("AR", "ARG", "Argentina"),
+    ("AS", "ASM", "American Samoa"),
+    ("AT", "AUT", "Austria"),
+    ("AU", "AUS", "Australia"),
+    ("AW", "ABW", "Aruba"),
+    ("AX", "ALA", "Aland Islands"),
+    ("AZ", "AZE", "Azerbaijan"),
+    ("BA", "BIH", "Bosnia and Herzegovina"),
+    ("BB", "BRB", "Barbados"),
+    ("BD", "BGD", "Bangladesh"),
+    ("BE", "BEL", "Belgium"),
+    ("BF", "BFA", "Burkina Faso"),
+    ("BG", "BGR", "Bulgaria"),
+    ("BH", "BHR", "Bahrain"),
+    ("BI", "BDI", "Burundi"),
+    ("BJ", "BEN", "Benin"),
+    ("BL", "BLM", "Saint Barthelemy"),
+    ("BM", "BMU", "Bermuda"),
+    ("BN", "BRN", "Brunei"),
+    ("BO", "BOL", "Bolivia"),
+    ("BQ", "BES", "Caribbean Netherlands"),
+    ("BR", "BRA", "Brazil"),
+    ("BS", "BHS", "Bahamas"),
+    ("BT", "BTN", "Bhutan"),
+    ("BV", "BVT", "Bouvet Island"),
+    ("BW", "BWA", "Botswana"),
+    ("BY", "BLR", "Belarus"),
+    ("BZ", "BLZ", "Belize"),
+    ("CA", "CAN", "Canada"),
+    ("CC", "CCK", "Cocos (Keeling) Islands"),
+    ("CD", "COD", "DR Congo"),
+    ("CF", "CAF", "Central African Republic"),
+    ("CG", "COG", "Congo"),
+    ("CH", "CHE", "Switzerland"),
+    ("CI", "CIV", "Cote d'Ivoire"),
+    ("CK", "COK", "Cook Islands"),
+    ("CL", "CHL", "Chile"),
+    ("CM", "CMR", "Cameroon"),
+    ("CN", "CHN", "China"),
+    ("CO", "COL", "Colombia"),
+    ("CR", "CRI", "Costa Rica"),
+    ("CU", "CUB", "Cuba"),
+    ("CV", "CPV", "Cape Verde"),
+    ("CW", "CUW", "Curacao"),
+    ("CX", "CXR", "Christmas Island"),
+    ("CY", "CYP", "Cyprus"),
+    ("CZ", "CZE", "Czechia"),
+    ("DE", "DEU", "Germany"),
+    ("DJ", "DJI", "Djibouti"),
+    ("DK", "DNK", "Denmark"),
+    ("DM", "DMA", "Dominica"),
+    ("DO", "DOM", "Dominican Republic"),
+    ("DZ", "DZA", "Algeria"),
+    ("EC", "ECU", "Ecuador"),
+    ("EE", "EST", "Estonia"),
+    ("EG", "EGY", "Egypt"),
+    ("EH", "ESH", "Western Sahara"),
+    ("ER", "ERI", "Eritrea"),
+    ("ES", "ESP", "Spain"),
+    ("ET", "ETH", "Ethiopia"),
+    ("FI", "FIN", "Finland"),
+    ("FJ", "FJI", "Fiji"),
+    ("FK", "FLK", "Falkland Islands"),
+    ("FM", "FSM", "Micronesia"),
+    ("FO", "FRO", "Faroe Islands"),
+    ("FR", "FRA", "France"),
+    ("GA", "GAB", "Gabon"),
+    ("GB", "GBR", "United Kingdom"),
+    ("GD", "GRD", "Grenada"),
+    ("GE", "GEO", "Georgia"),
+    ("GF", "GUF", "French Guiana"),
+    ("GG", "GGY", "Guernsey"),
+    ("GH", "GHA", "Ghana"),
+    ("GI", "GIB", "Gibraltar"),
+    ("GL", "GRL", "Greenland"),
+    ("GM", "GMB", "Gambia"),
+    ("GN", "GIN", "Guinea"),
+    ("GP", "GLP", "Guadeloupe"),
+    ("GQ", "GNQ", "Equatorial Guinea"),
+    ("GR", "GRC", "Greece"),
+    ("GS", "SGS", "South Georgia and the South Sandwich Islands"),
+    ("GT", "GTM", "Guatemala"),
+    ("GU", "GUM", "Guam"),
+    ("GW", "GNB", "Guinea-Bissau"),
+    ("GY", "GUY", "Guyana"),
+    ("HK", "HKG", "Hong Kong"),
+    ("HM", "HMD", "Heard Island and McDonald Islands"),
+    ("HN", "HND", "Honduras"),
+    ("HR", "HRV", "Croatia"),
+    ("HT", "HTI", "Haiti"),
+    ("HU", "HUN", "Hungary"),
+    ("ID", "IDN", "Indonesia"),
+    ("IE", "IRL", "Ireland"),
+    ("IL", "ISR", "Israel"),
+    ("IM", "IMN", "Isle of Man"),
+    ("IN", "IND", "India"),
+    ("IO", "IOT", "British Indian Ocean Territory"),
+    ("IQ", "IRQ", "Iraq"),
+    ("IR", "IRN", "Iran"),
+    ("IS", "ISL", "Iceland"),
+    ("IT", "ITA", "Italy"),
+    ("JE", "JEY", "Jersey"),
+    ("JM", "JAM", "Jamaica"),
+    ("JO", "JOR", "Jordan"),
+    ("JP", "JPN", "Japan"),
+    ("KE", "KEN", "Kenya"),
+    ("KG", "KGZ", "Kyrgyzstan"),
+    ("KH", "KHM", "Cambodia"),
+    ("KI", "KIR", "Kiribati"),
+    ("KM", "COM", "Comoros"),
+    ("KN", "KNA", "Saint Kitts and Nevis"),
+    ("KP", "PRK", "North Korea"),
+    ("KR", "KOR", "South Korea"),
+    ("KW", "KWT", "Kuwait"),
+    ("KY", "CYM", "Cayman Islands"),
+    ("KZ", "KAZ", "Kazakhstan"),
+    ("LA", "LAO", "Laos"),
+    ("LB", "LBN", "Lebanon"),
+    ("LC", "LCA", "Saint Lucia"),
+    ("LI", "LIE", "Liechtenstein"),
+    ("LK", "LKA", "Sri Lanka"),
+    ("LR", "LBR", "Liberia"),
+    ("LS", "LSO", "Lesotho"),
+    ("LT", "LTU", "Lithuania"),
+    ("LU", "LUX", "Luxembourg"),
+    ("LV", "LVA", "Latvia"),
+    ("LY", "LBY", "Libya"),
+    ("MA", "MAR", "Morocco"),
+    ("MC", "MCO", "Monaco"),
+    ("MD", "MDA", "Moldova"),
+    ("ME", "MNE", "Montenegro"),
+    ("MF", "MAF", "Saint Martin"),
+    ("MG", "MDG", "Madagascar"),
+    ("MH", "MHL", "Marshall Islands"),
+    ("MK", "MKD", "North Macedonia"),
+    ("ML", "MLI", "Mali"),
+    ("MM", "MMR", "Myanmar"),
+    ("MN", "MNG", "Mongolia"),
+    ("MO", "MAC", "Macao"),
+    ("MP", "MNP", "Northern Mariana Islands"),
+    ("MQ", "MTQ", "Martinique"),
+    ("MR", "MRT", "Mauritania"),
+    ("MS", "MSR", "Montserrat"),
+    ("MT", "MLT", "Malta"),
+    ("MU", "MUS", "Mauritius"),
+    ("MV", "MDV", "Maldives"),
+    ("MW", "MWI", "Malawi"),
+    ("MX", "MEX", "Mexico"),
+    ("MY", "MYS", "Malaysia"),
+    ("MZ", "MOZ", "Mozambique"),
+    ("NA", "NAM", "Namibia"),
+    ("NC", "NCL", "New Caledonia"),
+    ("NE", "NER", "Niger"),
+    ("NF", "NFK", "Norfolk Island"),
+    ("NG", "NGA", "Nigeria"),
+    ("NI", "NIC", "Nicaragua"),
+    ("NL", "NLD", "Netherlands"),
+    ("NO", "NOR", "Norway"),
+    ("NP", "NPL", "Nepal"),
+    ("NR", "NRU", "Nauru"),
+    ("NU", "NIU", "Niue"),
+    ("NZ", "NZL", "New Zealand"),
+    ("OM", "OMN", "Oman"),
+    ("PA", "PAN", "Panama"),
+    ("PE", "PER", "Peru"),
+    ("PF", "PYF", "French Polynesia"),
+    ("PG", "PNG", "Papua New Guinea"),
+    ("PH", "PHL", "Philippines"),
+    ("PK", "PAK", "Pakistan"),
+    ("PL", "POL", "Poland"),
+    ("PM", "SPM", "Saint Pierre and Miquelon"),
+    ("PN", "PCN", "Pitcairn"),
+    ("PR", "PRI", "Puerto Rico"),
+    ("PS", "PSE", "Palestine"),
+    ("PT", "PRT", "Portugal"),
+    ("PW", "PLW", "Palau"),
+    ("PY", "PRY", "Paraguay"),
+    ("QA", "QAT", "Qatar"),
+    ("RE", "REU", "Reunion"),
+    ("RO", "ROU", "Romania"),
+    ("RS", "SRB", "Serbia"),
+    ("RU", "RUS", "Russia"),
+    ("RW", "RWA", "Rwanda"),
+    ("SA", "SAU", "Saudi Arabia"),
+    ("SB", "SLB", "Solomon Islands"),
+    ("SC", "SYC", "Seychelles"),
+    ("SD", "SDN", "Sudan"),
+    ("SE", "SWE", "Sweden"),
+    ("SG", "SGP", "Singapore"),
+    ("SH", "SHN", "Saint Helena"),
+    ("SI", "SVN", "Slovenia"),
+    ("SJ", "SJM", "Svalbard and Jan Mayen"),
+    ("SK", "SVK", "Slovakia"),
+    ("SL", "SLE", "Sierra Leone"),
+    ("SM", "SMR", "San Marino"),
+    ("SN", "SEN", "Senegal"),
+    ("SO", "SOM", "Somalia"),
+    ("SR", "SUR", "Suriname"),
+    ("SS", "SSD", "South Sudan"),
+    ("ST", "STP", "Sao Tome and Principe"),
+    ("SV", "SLV", "El Salvador"),
+    ("SX", "SXM", "Sint Maarten"),
+    ("SY", "SYR", "Syria"),
+    ("SZ", "SWZ", "Eswatini"),
+    ("TC", "TCA", "Turks and Caicos Islands"),
+    ("TD", "TCD", "Chad"),
+    ("TF", "ATF", "French Southern Territories"),
+    ("TG", "TGO", "Togo"),
+    ("TH", "THA", "Thailand"),
+    ("TJ", "TJK", "Tajikistan"),
+    ("TK", "TKL", "Tokelau"),
+    ("TL", "TLS", "Timor-Leste"),
+    ("TM", "TKM", "Turkmenistan"),
+    ("TN", "TUN", "Tunisia"),
+    ("TO", "TON", "Tonga"),
+    ("TR", "TUR", "Turkey"),
+    ("TT", "TTO", "Trinidad and Tobago"),
+    ("TV", "TUV", "Tuvalu"),
+    ("TW", "TWN", "Taiwan"),
+    ("TZ", "TZA", "Tanzania"),
+    ("UA", "UKR", "Ukraine"),
+    ("UG", "UGA", "Uganda"),
+    ("UM", "UMI", "US Minor Outlying Islands"),
+    ("US", "USA", "United States"),
+    ("UY", "URY", "Uruguay"),
+    ("UZ", "UZB", "Uzbekistan"),
+    ("VA", "VAT", "Vatican City"),
+    ("VC", "VCT", "Saint Vincent and the Grenadines"),
+    ("VE", "VEN", "Venezuela"),
+    ("VG", "VGB", "British Virgin Islands"),
+    ("VI", "VIR", "US Virgin Islands"),
+    ("VN", "VNM", "Vietnam"),
+    ("VU", "VUT", "Vanuatu"),
+    ("WF", "WLF", "Wallis and Futuna"),
+    ("WS", "WSM", "Samoa"),
+    ("YE", "YEM", "Yemen"),
+    ("YT", "MYT", "Mayotte"),
+    ("ZA", "ZAF", "South Africa"),
+    ("ZM", "ZMB", "Zambia"),
+    ("ZW", "ZWE", "Zimbabwe"),
+];
@@ -0,0 +1,133 @@
+use crate::proxy::{ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
+use moka::future::Cache;
+use std::time::Duration;
+
+/// Metadata for one `ip:port`, merged across every provider that listed it.
+#[derive(Debug, Clone)]
+pub struct MergedMetadata {
+    pub meta: ProxyMetadata,
+    pub providers: Vec<String>,
+}
+
+impl MergedMetadata {
+    fn new(provider: &str, meta: &ProxyMetadata) -> Self {
+        Self {
+            meta: meta.clone(),
+            providers: vec![provider.to_string()],
+        }
+    }
+
+    /// Number of provider lists vouching for this proxy.
+    pub fn sources(&self) -> usize {
+        self.providers.len()
+    }
+
+    fn merge(&mut self, provider: &str, meta: &ProxyMetadata) {
+        if !self.providers.iter().any(|p| p == provider) {
+            self.providers.push(provider.to_string());
+        }
+        if type_specificity(&meta.kind) > type_specificity(&self.meta.kind) {
+            self.meta.kind = meta.kind.clone();
+        }
+        if !self.meta.country.is_known() && meta.country.is_known() {
+            self.meta.country = meta.country;
+        }
+        if self.meta.geoip_country.is_none() {
+            self.meta.geoip_country = meta.geoip_country;
+        }
+        if self.meta.asn.is_none() {
+            self.meta.asn = meta.asn.clone();
+        }
+        if self.meta.anonymity.is_none() {
+            self.meta.anonymity = meta.anonymity;
+        }
+        if self.meta.hostname.is_none() {
+            self.meta.hostname = meta.hostname.clone();
+        }
+        if self.meta.rank.is_none() {
+            self.meta.rank = meta.rank;
+        }
+        if self.meta.google.is_none() {
+            self.meta.google = meta.google;
+        }
+        if meta.last_checked > self.meta.last_checked {
+            self.meta.last_checked = meta.last_checked;
+        }
+    }
+}
+
+// HTTPS and SOCKS say more about a proxy than plain HTTP, which says more than nothing.
+fn type_specificity(kind: &ProxyType) -> u8 {
+    match kind {
+        ProxyType::Unknown => 0,
+        ProxyType::Http => 1,
+        ProxyType::Https
+        | ProxyType::Socks4
+        | ProxyType::Socks4a
+        | ProxyType::Socks5
+        | ProxyType::Socks5h => 2,
+    }
+}
+
+// How long a dispatched address is left alone before it may be verified again
+const DEFAULT_DISPATCH_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Collapses duplicate `ip:port` entries coming from different providers so each
+/// address is verified once, while keeping track of every list it came from.
+///
+/// Dispatches are kept apart from the provenance entries, which every listing
+/// refreshes, so a claim expires even while the address stays listed.
+#[derive(Clone)]
+pub struct Deduplicator {
+    entries: Cache<ProxyAddr, MergedMetadata>,
+    dispatched: Cache<ProxyAddr, ()>,
+}
+
+impl Deduplicator {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Cache::builder().time_to_live(ttl).build(),
+            dispatched: Cache::builder().time_to_live(DEFAULT_DISPATCH_TTL).build(),
+        }
+    }
+
+    /// How long after a dispatch `claim` refuses the address again.
+    pub fn with_dispatch_ttl(mut self, ttl: Duration) -> Self {
+        self.dispatched = Cache::builder().time_to_live(ttl).build();
+        self
+    }
+
+    /// Merge a provider's entry into the table.
+    pub async fn record(&self, provider: &str, meta: &ProxyMetadata) {
+        self.entries
+            .entry_by_ref(&meta.addr)
+            .and_upsert_with(|existing| {
+                let merged = match existing {
+                    Some(entry) => {
+                        let mut merged = entry.into_value();
+                        merged.merge(provider, meta);
+                        merged
+                    }
+                    None => MergedMetadata::new(provider, meta),
+                };
+                std::future::ready(merged)
+            })
+            .await;
+    }
+
+    /// Returns true if the caller should dispatch a verification job for `addr`,
+    /// i.e. no other provider has already done so within the dispatch TTL.
+    pub async fn claim(&self, addr: &ProxyAddr) -> bool {
+        self.dispatched.entry_by_ref(addr).or_insert(()).await.is_fresh()
+    }
+
+    /// Whether a verification job for `addr` was dispatched within the dispatch TTL.
+    pub async fn is_claimed(&self, addr: &ProxyAddr) -> bool {
+        self.dispatched.contains_key(addr)
+    }
+
+    pub async fn get(&self, addr: &ProxyAddr) -> Option<MergedMetadata> {
+        self.entries.get(addr).await
+    }
+}
@@ -0,0 +1,180 @@
+use crate::country::Country;
+use crate::proxy::Proxy;
+use crate::proxy_addr::{Host, ProxyAddr};
+use moka::future::Cache;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Spread requirements for a batch of proxies (see `ProxyGenerator::get_batch`).
+#[derive(Debug, Clone, Default)]
+pub struct DiversityPolicy {
+    pub max_per_country: Option<usize>,
+    pub max_per_provider: Option<usize>, // A proxy counts against every provider listing it
+    pub max_per_subnet: Option<usize>, // Per /24 for IPv4, /48 for IPv6
+    pub min_per_country: HashMap<Country, usize>,
+}
+
+impl DiversityPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_per_country(mut self, max: usize) -> Self {
+        self.max_per_country = Some(max);
+        self
+    }
+
+    pub fn with_max_per_provider(mut self, max: usize) -> Self {
+        self.max_per_provider = Some(max);
+        self
+    }
+
+    pub fn with_max_per_subnet(mut self, max: usize) -> Self {
+        self.max_per_subnet = Some(max);
+        self
+    }
+
+    /// Require at least `min` proxies from each of `countries` (codes or names).
+    /// Unrecognised countries are ignored.
+    pub fn with_min_per_country(mut self, countries: Vec<String>, min: usize) -> Self {
+        for country in countries {
+            let country = Country::parse(&country);
+            if country.is_known() {
+                self.min_per_country.insert(country, min);
+            }
+        }
+        self
+    }
+}
+
+/// Tracks a batch being assembled and decides which proxies still fit the policy.
+#[derive(Debug)]
+pub struct DiversityTracker<'a> {
+    policy: &'a DiversityPolicy,
+    target: usize,
+    accepted: usize,
+    addrs: HashSet<ProxyAddr>,
+    countries: HashMap<Country, usize>,
+    providers: HashMap<String, usize>,
+    subnets: HashMap<String, usize>,
+}
+
+impl<'a> DiversityTracker<'a> {
+    pub fn new(policy: &'a DiversityPolicy, target: usize) -> Self {
+        Self {
+            policy,
+            target,
+            accepted: 0,
+            addrs: HashSet::new(),
+            countries: HashMap::new(),
+            providers: HashMap::new(),
+            subnets: HashMap::new(),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.accepted >= self.target
+    }
+
+    /// Countries whose minimum count is not met yet.
+    pub fn wanted_countries(&self) -> Vec<Country> {
+        self.policy
+            .min_per_country
+            .keys()
+            .filter(|c| self.missing(**c) > 0)
+            .copied()
+            .collect()
+    }
+
+    /// Adds `proxy` to the batch if it fits, returning whether it was taken.
+    pub fn accept(&mut self, proxy: &Proxy) -> bool {
+        if self.is_full() || self.addrs.contains(&proxy.addr) {
+            return false;
+        }
+
+        let country = proxy.country;
+        let subnet = subnet_key(&proxy.addr);
+
+        // A proxy listed by several providers counts against each of them
+        if exceeds(self.policy.max_per_country, &self.countries, &country)
+            || proxy
+                .providers
+                .iter()
+                .any(|p| exceeds(self.policy.max_per_provider, &self.providers, p))
+            || exceeds(self.policy.max_per_subnet, &self.subnets, &subnet)
+        {
+            return false;
+        }
+
+        // Keep enough free slots to still meet every minimum
+        let remaining = self.target - self.accepted;
+        if self.missing(country) == 0 && self.deficit() >= remaining {
+            return false;
+        }
+
+        self.addrs.insert(proxy.addr.clone());
+        *self.countries.entry(country).or_default() += 1;
+        for provider in &proxy.providers {
+            *self.providers.entry(provider.clone()).or_default() += 1;
+        }
+        *self.subnets.entry(subnet).or_default() += 1;
+        self.accepted += 1;
+        true
+    }
+
+    fn missing(&self, country: Country) -> usize {
+        let min = self.policy.min_per_country.get(&country).copied().unwrap_or(0);
+        min.saturating_sub(self.countries.get(&country).copied().unwrap_or(0))
+    }
+
+    fn deficit(&self) -> usize {
+        self.policy
+            .min_per_country
+            .keys()
+            .map(|c| self.missing(*c))
+            .sum()
+    }
+}
+
+/// Exit IPs already handed out, each forgotten `ttl` after its first use, so
+/// rotating through proxies really changes the visible address.
+#[derive(Clone)]
+pub struct ExitIpSet {
+    seen: Cache<IpAddr, ()>,
+}
+
+impl ExitIpSet {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            seen: Cache::builder().time_to_live(ttl).build(),
+        }
+    }
+
+    /// Marks `ip` as handed out, returning false if it already was within the TTL.
+    pub async fn first_use(&self, ip: IpAddr) -> bool {
+        self.seen.entry(ip).or_insert(()).await.is_fresh()
+    }
+}
+
+fn exceeds<K: Eq + Hash>(max: Option<usize>, counts: &HashMap<K, usize>, key: &K) -> bool {
+    match max {
+        Some(max) => counts.get(key).copied().unwrap_or(0) >= max,
+        None => false,
+    }
+}
+
+fn subnet_key(addr: &ProxyAddr) -> String {
+    match &addr.host {
+        Host::Ip(IpAddr::V4(ip)) => {
+            let o = ip.octets();
+            format!("{}.{}.{}.0/24", o[0], o[1], o[2])
+        }
+        Host::Ip(IpAddr::V6(ip)) => {
+            let s = ip.segments();
+            format!("{:x}:{:x}:{:x}::/48", s[0], s[1], s[2])
+        }
+        Host::Domain(domain) => domain.clone(),
+    }
+}
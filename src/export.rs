@@ -0,0 +1,179 @@
+use crate::proxy::{Proxy, ProxyType};
+use crate::proxy_addr::Host;
+use anyhow::{anyhow, Result};
+use log::warn;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::str::FromStr;
+
+/// Output formats understood by `export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Plain,       // host:port, one per line
+    Urls,        // scheme://[user:pass@]host:port, one per line
+    Proxychains, // [ProxyList] section of proxychains.conf
+    Clash,       // `proxies:` list for Clash configs
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "plain" | "txt" | "ip:port" => Ok(ExportFormat::Plain),
+            "urls" | "url" => Ok(ExportFormat::Urls),
+            "proxychains" => Ok(ExportFormat::Proxychains),
+            "clash" | "yaml" => Ok(ExportFormat::Clash),
+            _ => Err(anyhow!("unknown export format: {}", s)),
+        }
+    }
+}
+
+/// Render `proxies` in `format`. Everything except `Plain` includes credentials.
+pub fn export(proxies: &[Proxy], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Json => to_json(proxies),
+        ExportFormat::Csv => to_csv(proxies),
+        ExportFormat::Plain => Ok(to_plain(proxies)),
+        ExportFormat::Urls => Ok(to_urls(proxies)),
+        ExportFormat::Proxychains => Ok(to_proxychains(proxies)),
+        ExportFormat::Clash => to_clash(proxies),
+    }
+}
+
+/// Like `export`, writing to `writer`.
+pub fn write_to<W: Write>(writer: &mut W, proxies: &[Proxy], format: ExportFormat) -> Result<()> {
+    writer.write_all(export(proxies, format)?.as_bytes())?;
+    Ok(())
+}
+
+pub fn to_json(proxies: &[Proxy]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(proxies)?)
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    host: String,
+    port: u16,
+    #[serde(rename = "type")]
+    kind: &'a ProxyType,
+    country: Option<&'static str>,
+    latency_ms: u64,
+    uptime: f64,
+    providers: String,
+    exit_ip: Option<String>,
+    asn: Option<u32>,
+    network_class: Option<String>,
+    url: String,
+}
+
+/// One row per proxy with flat columns; `providers` is `;`-separated.
+pub fn to_csv(proxies: &[Proxy]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for proxy in proxies {
+        writer.serialize(CsvRow {
+            host: bare_host(&proxy.addr.host),
+            port: proxy.addr.port,
+            kind: &proxy.kind,
+            country: proxy.country.code(),
+            latency_ms: proxy.latency.as_millis() as u64,
+            uptime: proxy.uptime,
+            providers: proxy.providers.join(";"),
+            exit_ip: proxy.exit_ip.map(|ip| ip.to_string()),
+            asn: proxy.asn.as_ref().map(|a| a.number),
+            network_class: proxy.asn.as_ref().map(|a| a.class.to_string()),
+            url: proxy.url(),
+        })?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// `host:port` lines, without scheme or credentials.
+pub fn to_plain(proxies: &[Proxy]) -> String {
+    proxies.iter().map(|p| p.addr.host_port() + "\n").collect()
+}
+
+pub fn to_urls(proxies: &[Proxy]) -> String {
+    proxies.iter().map(|p| p.url() + "\n").collect()
+}
+
+/// `[ProxyList]` section: `type host port [user pass]` per line. proxychains
+/// splits on whitespace and has no quoting, so proxies whose credentials contain
+/// any are left out.
+pub fn to_proxychains(proxies: &[Proxy]) -> String {
+    let mut out = String::from("[ProxyList]\n");
+    for proxy in proxies {
+        if let Some(c) = &proxy.addr.credentials {
+            if c.username.contains(char::is_whitespace) || c.password.contains(char::is_whitespace) {
+                warn!("leaving {} out of proxychains list: whitespace in credentials", proxy.addr);
+                continue;
+            }
+        }
+        let kind = match proxy.kind {
+            ProxyType::Socks4 | ProxyType::Socks4a => "socks4",
+            ProxyType::Socks5 | ProxyType::Socks5h => "socks5",
+            _ => "http",
+        };
+        let _ = write!(out, "{} {} {}", kind, bare_host(&proxy.addr.host), proxy.addr.port);
+        if let Some(c) = &proxy.addr.credentials {
+            let _ = write!(out, " {} {}", c.username, c.password);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Serialize)]
+struct ClashConfig {
+    proxies: Vec<ClashProxy>,
+}
+
+#[derive(Serialize)]
+struct ClashProxy {
+    name: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    server: String,
+    port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+/// `proxies:` list for Clash. Clash has no SOCKS4 support, so those are left out.
+pub fn to_clash(proxies: &[Proxy]) -> Result<String> {
+    let proxies = proxies
+        .iter()
+        .filter_map(|proxy| {
+            let kind = match proxy.kind {
+                ProxyType::Socks4 | ProxyType::Socks4a => return None,
+                ProxyType::Socks5 | ProxyType::Socks5h => "socks5",
+                _ => "http",
+            };
+            let credentials = proxy.addr.credentials.as_ref();
+            Some(ClashProxy {
+                name: format!("{} {}", proxy.country, proxy.addr.host_port()),
+                kind,
+                server: bare_host(&proxy.addr.host),
+                port: proxy.addr.port,
+                username: credentials.map(|c| c.username.clone()),
+                password: credentials.map(|c| c.password.clone()),
+            })
+        })
+        .collect();
+    Ok(serde_yaml::to_string(&ClashConfig { proxies })?)
+}
+
+// Tools taking host and port separately want IPv6 without brackets
+fn bare_host(host: &Host) -> String {
+    match host {
+        Host::Ip(ip) => ip.to_string(),
+        Host::Domain(domain) => domain.clone(),
+    }
+}
@@ -1,12 +1,27 @@
+use crate::asn::NetworkClass;
+use crate::country::CountrySelector;
 use crate::proxy::{Proxy, ProxyMetadata, ProxyType};
+use log::warn;
+use std::net::IpAddr;
 use std::time::Duration;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpFamily {
+    V4,
+    V6,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ProxyFilter {
     pub proxy_types: Option<Vec<ProxyType>>,
-    pub include_countries: Option<Vec<String>>,
-    pub exclude_countries: Option<Vec<String>>,
+    pub include_countries: Option<Vec<CountrySelector>>,
+    pub exclude_countries: Option<Vec<CountrySelector>>,
     pub max_latency: Option<Duration>,
+    pub ip_family: Option<IpFamily>,
+    pub include_asns: Option<Vec<u32>>,
+    pub exclude_asns: Option<Vec<u32>>,
+    pub include_network_classes: Option<Vec<NetworkClass>>,
+    pub exclude_network_classes: Option<Vec<NetworkClass>>,
 }
 
 impl ProxyFilter {
@@ -19,13 +34,14 @@ impl ProxyFilter {
         self
     }
 
+    /// Countries as alpha-2/alpha-3 codes or names, or regions such as "EU" or "ASIA".
     pub fn with_include_countries(mut self, countries: Vec<String>) -> Self {
-        self.include_countries = Some(countries);
+        self.include_countries = Some(parse_selectors(&countries));
         self
     }
 
     pub fn with_exclude_countries(mut self, countries: Vec<String>) -> Self {
-        self.exclude_countries = Some(countries);
+        self.exclude_countries = Some(parse_selectors(&countries));
         self
     }
 
@@ -34,6 +50,31 @@ impl ProxyFilter {
         self
     }
 
+    pub fn with_ip_family(mut self, family: IpFamily) -> Self {
+        self.ip_family = Some(family);
+        self
+    }
+
+    pub fn with_include_asns(mut self, asns: Vec<u32>) -> Self {
+        self.include_asns = Some(asns);
+        self
+    }
+
+    pub fn with_exclude_asns(mut self, asns: Vec<u32>) -> Self {
+        self.exclude_asns = Some(asns);
+        self
+    }
+
+    pub fn with_include_network_classes(mut self, classes: Vec<NetworkClass>) -> Self {
+        self.include_network_classes = Some(classes);
+        self
+    }
+
+    pub fn with_exclude_network_classes(mut self, classes: Vec<NetworkClass>) -> Self {
+        self.exclude_network_classes = Some(classes);
+        self
+    }
+
     pub fn filter_metadata(&self, meta: &ProxyMetadata) -> bool {
         if let Some(types) = &self.proxy_types {
             if !types.contains(&meta.kind) {
@@ -41,16 +82,53 @@ impl ProxyFilter {
             }
         }
 
+        if let Some(family) = self.ip_family {
+            let matches = match meta.ip() {
+                Some(IpAddr::V4(_)) => family == IpFamily::V4,
+                Some(IpAddr::V6(_)) => family == IpFamily::V6,
+                None => false, // Hostname, family unknown until resolved
+            };
+            if !matches {
+                return false;
+            }
+        }
+
         if let Some(includes) = &self.include_countries {
-            // Case-insensitive comparison? Let's assume input matches implementation (usually uppercase codes)
-            // But to be safe, exact match for now.
-            if !includes.iter().any(|c| c.eq_ignore_ascii_case(&meta.country)) {
+            if !includes.iter().any(|c| c.matches(meta.country)) {
                 return false;
             }
         }
 
         if let Some(excludes) = &self.exclude_countries {
-            if excludes.iter().any(|c| c.eq_ignore_ascii_case(&meta.country)) {
+            if excludes.iter().any(|c| c.matches(meta.country)) {
+                return false;
+            }
+        }
+
+        // ASN filters need an ASN database; without one, includes drop everything
+        let asn = meta.asn.as_ref();
+        let class = asn.map(|a| a.class).unwrap_or(NetworkClass::Unknown);
+
+        if let Some(includes) = &self.include_asns {
+            if !asn.is_some_and(|a| includes.contains(&a.number)) {
+                return false;
+            }
+        }
+
+        if let Some(excludes) = &self.exclude_asns {
+            if asn.is_some_and(|a| excludes.contains(&a.number)) {
+                return false;
+            }
+        }
+
+        if let Some(includes) = &self.include_network_classes {
+            if !includes.contains(&class) {
+                return false;
+            }
+        }
+
+        if let Some(excludes) = &self.exclude_network_classes {
+            if excludes.contains(&class) {
                 return false;
             }
         }
@@ -60,11 +138,9 @@ impl ProxyFilter {
 
     pub fn filter_proxy(&self, proxy: &Proxy) -> bool {
         // Metadata filters
-        let meta = ProxyMetadata {
-            addr: proxy.addr.clone(),
-            kind: proxy.kind.clone(),
-            country: proxy.country.clone(),
-        };
+        let mut meta = ProxyMetadata::new(proxy.addr.clone(), proxy.kind.clone(), proxy.country);
+        meta.geoip_country = proxy.geoip_country;
+        meta.asn = proxy.asn.clone();
 
         if !self.filter_metadata(&meta) {
             return false;
@@ -80,3 +156,16 @@ impl ProxyFilter {
         true
     }
 }
+
+fn parse_selectors(countries: &[String]) -> Vec<CountrySelector> {
+    countries
+        .iter()
+        .filter_map(|c| match c.parse() {
+            Ok(selector) => Some(selector),
+            Err(e) => {
+                warn!("ignoring country filter entry: {}", e);
+                None
+            }
+        })
+        .collect()
+}
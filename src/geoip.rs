@@ -0,0 +1,111 @@
+use crate::country::Country;
+use crate::proxy::ProxyMetadata;
+use anyhow::{anyhow, Result};
+use ipnet::IpNet;
+use maxminddb::{geoip2, Reader};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+
+enum Source {
+    Mmdb(Reader<Vec<u8>>),
+    Cidr(Vec<PrefixTable>), // Longest prefix first
+}
+
+// Every range of one prefix length, sorted by network for binary search
+struct PrefixTable {
+    len: u8,
+    nets: Vec<(IpNet, Country)>,
+}
+
+/// Offline IP-to-country lookup, used to fill in countries providers don't report.
+pub struct GeoIp {
+    source: Source,
+}
+
+impl GeoIp {
+    /// Load a MaxMind-format database (GeoLite2-Country, DB-IP, ...).
+    pub fn open_mmdb<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let reader = Reader::open_readfile(path)?;
+        Ok(Self {
+            source: Source::Mmdb(reader),
+        })
+    }
+
+    /// Load a `cidr,country` CSV, e.g. `1.0.0.0/24,AU`. Blank lines, `#` comments
+    /// and lines that don't parse (such as a header) are skipped.
+    pub fn open_csv<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let mut ranges = Vec::new();
+
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((cidr, country)) = line.split_once(',') else {
+                continue;
+            };
+            if let Ok(net) = cidr.trim().parse::<IpNet>() {
+                let country = Country::parse(country.trim().trim_matches('"'));
+                if country.is_known() {
+                    ranges.push((net.trunc(), country));
+                }
+            }
+        }
+
+        if ranges.is_empty() {
+            return Err(anyhow!("no CIDR ranges found"));
+        }
+
+        // Most specific range wins; the first line wins among equal ranges
+        ranges.sort_by(|(a, _), (b, _)| b.prefix_len().cmp(&a.prefix_len()).then(a.cmp(b)));
+        ranges.dedup_by(|(a, _), (b, _)| a == b);
+        let mut tables: Vec<PrefixTable> = Vec::new();
+        for (net, country) in ranges {
+            match tables.last_mut() {
+                Some(table) if table.len == net.prefix_len() => table.nets.push((net, country)),
+                _ => tables.push(PrefixTable {
+                    len: net.prefix_len(),
+                    nets: vec![(net, country)],
+                }),
+            }
+        }
+        Ok(Self {
+            source: Source::Cidr(tables),
+        })
+    }
+
+    pub fn lookup(&self, ip: IpAddr) -> Option<Country> {
+        match &self.source {
+            Source::Mmdb(reader) => {
+                let record: geoip2::Country = reader.lookup(ip).ok()?;
+                record
+                    .country
+                    .or(record.registered_country)
+                    .and_then(|c| c.iso_code)
+                    .map(Country::parse)
+                    .filter(Country::is_known)
+            }
+            Source::Cidr(tables) => tables.iter().find_map(|table| {
+                // Too long for this address family
+                let net = IpNet::new(ip, table.len).ok()?.trunc();
+                let idx = table.nets.binary_search_by(|(n, _)| n.cmp(&net)).ok()?;
+                Some(table.nets[idx].1)
+            }),
+        }
+    }
+
+    /// Record the database country on `meta`, and use it as `country` when the
+    /// provider didn't give one.
+    pub fn enrich(&self, meta: &mut ProxyMetadata) {
+        let Some(country) = meta.ip().and_then(|ip| self.lookup(ip)) else {
+            return;
+        };
+
+        if !meta.country.is_known() {
+            meta.country = country;
+        }
+        meta.geoip_country = Some(country);
+    }
+}
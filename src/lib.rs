@@ -1,9 +1,20 @@
 pub mod proxy;
+pub mod proxy_addr;
+pub mod country;
 pub mod filter;
 pub mod provider;
 pub mod proxy_generator;
 pub mod providers;
 pub mod verification;
 pub mod configuration;
+pub mod dedupe;
+pub mod sampling;
+pub mod priority;
+pub mod diversity;
+pub mod asn;
+pub mod export;
+pub mod pac;
+#[cfg(feature = "geoip")]
+pub mod geoip;
 
 pub use proxy_generator::ProxyGenerator;
@@ -0,0 +1,116 @@
+use crate::country::CountrySelector;
+use crate::proxy::{Proxy, ProxyType};
+use crate::proxy_addr::Host;
+use log::warn;
+use std::fmt::Write;
+
+/// Routes hosts under `domain` through proxies from `country`.
+#[derive(Debug, Clone)]
+pub struct PacRule {
+    pub domain: String, // Matches the domain itself and its subdomains
+    pub country: CountrySelector,
+}
+
+/// How `render_pac` builds the script.
+#[derive(Debug, Clone)]
+pub struct PacConfig {
+    pub failover: usize, // Proxies tried in turn for each route
+    pub rules: Vec<PacRule>,
+    pub direct_fallback: bool, // End every route with DIRECT
+}
+
+impl Default for PacConfig {
+    fn default() -> Self {
+        Self {
+            failover: 3,
+            rules: Vec::new(),
+            direct_fallback: true,
+        }
+    }
+}
+
+impl PacConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_failover(mut self, failover: usize) -> Self {
+        self.failover = failover.max(1);
+        self
+    }
+
+    /// Send `domain` (e.g. "example.com" or "*.example.com") through proxies
+    /// from `country`, a country code, name or region. Invalid entries are
+    /// ignored with a warning.
+    pub fn with_rule(mut self, domain: &str, country: &str) -> Self {
+        let domain = domain.trim().trim_start_matches("*.").trim_start_matches('.');
+        let country = match country.parse::<CountrySelector>() {
+            Ok(country) => country,
+            Err(e) => {
+                warn!("ignoring PAC rule for {}: {}", domain, e);
+                return self;
+            }
+        };
+        match domain.parse::<Host>() {
+            Ok(Host::Domain(domain)) => self.rules.push(PacRule { domain, country }),
+            _ => warn!("ignoring PAC rule: invalid domain {}", domain),
+        }
+        self
+    }
+
+    pub fn with_direct_fallback(mut self, direct_fallback: bool) -> Self {
+        self.direct_fallback = direct_fallback;
+        self
+    }
+}
+
+/// Render a Proxy Auto-Config script from verified `proxies`, fastest first.
+///
+/// PAC has no way to carry credentials, so the browser will prompt for them if
+/// an authenticated proxy is used.
+pub fn render_pac(proxies: &[Proxy], config: &PacConfig) -> String {
+    let mut sorted: Vec<&Proxy> = proxies.iter().collect();
+    sorted.sort_by_key(|p| p.latency);
+
+    let mut script = String::from("function FindProxyForURL(url, host) {\n");
+    for rule in &config.rules {
+        let matching: Vec<&Proxy> = sorted
+            .iter()
+            .copied()
+            .filter(|p| rule.country.matches(p.country))
+            .collect();
+        if matching.is_empty() {
+            warn!("no proxies for PAC rule {}, using fallback only", rule.domain);
+        }
+        let _ = writeln!(
+            script,
+            "    if (host == \"{0}\" || dnsDomainIs(host, \".{0}\"))\n        return \"{1}\";",
+            rule.domain,
+            route(&matching, config)
+        );
+    }
+    let _ = writeln!(script, "    return \"{}\";", route(&sorted, config));
+    script.push_str("}\n");
+    script
+}
+
+// "PROXY a:1; SOCKS5 b:2; DIRECT"
+fn route(proxies: &[&Proxy], config: &PacConfig) -> String {
+    let mut entries: Vec<String> = proxies
+        .iter()
+        .take(config.failover)
+        .map(|p| format!("{} {}", directive(&p.kind), p.addr.host_port()))
+        .collect();
+    if config.direct_fallback || entries.is_empty() {
+        entries.push("DIRECT".to_string());
+    }
+    entries.join("; ")
+}
+
+fn directive(kind: &ProxyType) -> &'static str {
+    match kind {
+        ProxyType::Socks4 | ProxyType::Socks4a => "SOCKS",
+        ProxyType::Socks5 | ProxyType::Socks5h => "SOCKS5",
+        ProxyType::Http | ProxyType::Https | ProxyType::Unknown => "PROXY",
+    }
+}
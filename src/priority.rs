@@ -0,0 +1,112 @@
+use crate::proxy::Proxy;
+use std::time::Duration;
+
+/// Weights of the terms making up a proxy's score. Each term is normalised to
+/// `0.0..=1.0`, higher is better.
+#[derive(Debug, Clone)]
+pub struct ScoreWeights {
+    pub latency: f64,
+    pub uptime: f64,
+    pub sources: f64,
+}
+
+impl ScoreWeights {
+    pub fn score(&self, proxy: &Proxy) -> f64 {
+        self.latency * latency_term(proxy.latency)
+            + self.uptime * proxy.uptime
+            + self.sources * sources_term(proxy.sources())
+    }
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            latency: 1.0,
+            uptime: 0.5,
+            sources: 0.25,
+        }
+    }
+}
+
+// 0 s -> 1.0, 1 s -> 0.5, 2 s -> 0.33
+fn latency_term(latency: Duration) -> f64 {
+    1.0 / (1.0 + latency.as_secs_f64())
+}
+
+// 1 list -> 0.0, 2 lists -> 0.5, 4 lists -> 0.75
+fn sources_term(sources: usize) -> f64 {
+    if sources == 0 {
+        return 0.0;
+    }
+    1.0 - 1.0 / sources as f64
+}
+
+#[derive(Debug, Clone)]
+pub struct PriorityConfig {
+    pub weights: ScoreWeights,
+    pub capacity: usize, // Lowest-scored proxies are dropped beyond this
+}
+
+impl PriorityConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_weights(mut self, weights: ScoreWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// `ProxyGenerator::set_priority` treats 0 as priority disabled.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        Self {
+            weights: ScoreWeights::default(),
+            capacity: 100,
+        }
+    }
+}
+
+/// Bounded buffer of verified proxies that hands out the best-scored one first.
+#[derive(Debug)]
+pub struct PriorityBuffer {
+    config: PriorityConfig,
+    entries: Vec<(f64, Proxy)>, // Sorted by ascending score
+}
+
+impl PriorityBuffer {
+    pub fn new(config: PriorityConfig) -> Self {
+        Self {
+            config,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, proxy: Proxy) {
+        let score = self.config.weights.score(&proxy);
+        let idx = self.entries.partition_point(|(s, _)| *s <= score);
+        self.entries.insert(idx, (score, proxy));
+
+        if self.entries.len() > self.config.capacity {
+            self.entries.remove(0);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<Proxy> {
+        self.entries.pop().map(|(_, proxy)| proxy)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
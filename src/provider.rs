@@ -1,9 +1,53 @@
-use crate::proxy::ProxyMetadata;
+use crate::providers::fixtures::ClientMode;
+use crate::providers::health::ParseReport;
+use crate::proxy::{Proxy, ProxyMetadata};
 use async_trait::async_trait;
 
 #[async_trait]
 pub trait Provider: Send + Sync {
     async fn list(&mut self) -> anyhow::Result<Vec<ProxyMetadata>>;
-    fn name(&self) -> &'static str;
-    fn set_proxy(&mut self, proxy: String);
+    fn name(&self) -> &str;
+    fn set_proxy(&mut self, proxy: Proxy);
+
+    /// Whether `list` returns the best entries first (e.g. a speed ranking).
+    fn ranked(&self) -> bool {
+        false
+    }
+
+    /// Report of the parse behind the last `list` call, if that call fetched
+    /// afresh. Returned once.
+    fn take_report(&mut self) -> Option<ParseReport> {
+        None
+    }
+
+    /// Record or replay fetched pages; providers that fetch nothing ignore it.
+    fn set_client_mode(&mut self, _mode: ClientMode) {}
+}
+
+// Lets factories hand out `Box<dyn Provider>`
+#[async_trait]
+impl<P: Provider + ?Sized> Provider for Box<P> {
+    async fn list(&mut self) -> anyhow::Result<Vec<ProxyMetadata>> {
+        (**self).list().await
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn set_proxy(&mut self, proxy: Proxy) {
+        (**self).set_proxy(proxy)
+    }
+
+    fn ranked(&self) -> bool {
+        (**self).ranked()
+    }
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        (**self).take_report()
+    }
+
+    fn set_client_mode(&mut self, mode: ClientMode) {
+        (**self).set_client_mode(mode)
+    }
 }
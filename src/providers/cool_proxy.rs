@@ -1,7 +1,10 @@
-use super::base::BaseProvider;
-use super::new_client;
+use super::base::{BaseProvider, ProviderOptions};
+use super::fixtures::ClientMode;
+use super::health::ParseReport;
 use crate::provider::Provider;
-use crate::proxy::{ProxyMetadata, ProxyType};
+use crate::country::Country;
+use crate::proxy::{Proxy, ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
@@ -12,25 +15,33 @@ const COOL_PROXY_URL: &str = "https://www.cool-proxy.net/proxies/http_proxy_list
 
 pub struct CoolProxy {
     base: BaseProvider,
+    url: String,
 }
 
 impl CoolProxy {
     pub fn new() -> Self {
         Self {
             base: BaseProvider::new(),
+            url: COOL_PROXY_URL.to_string(),
         }
     }
 
-    async fn load_internal(&mut self) -> Result<Vec<ProxyMetadata>> {
-        if !self.base.should_update() {
-             return Ok(self.base.cached_list());
+    pub fn with_options(mut self, options: ProviderOptions) -> Self {
+        if let Some(url) = &options.url {
+            self.url = url.clone();
         }
+        self.base.apply(&options);
+        self
+    }
 
-        let client = new_client(if self.base.proxy_upstream.is_empty() { None } else { Some(&self.base.proxy_upstream) })?;
-        let resp = client.get(COOL_PROXY_URL).send().await?;
-        let body = resp.text().await?;
+    /// Proxies in a downloaded list page.
+    pub fn parse(body: &str) -> Result<Vec<ProxyMetadata>> {
+        Self::parse_with_report(body, &mut ParseReport::new())
+    }
 
-        let doc = Html::parse_document(&body);
+    /// `parse`, noting in `report` how many rows it saw and why any were dropped.
+    pub fn parse_with_report(body: &str, report: &mut ParseReport) -> Result<Vec<ProxyMetadata>> {
+        let doc = Html::parse_document(body);
         let ip_selector = Selector::parse(r#"#main table tr td:nth-child(1):not([colspan]) script"#).unwrap();
         let port_selector = Selector::parse(r#"#main table tr td:nth-child(2)"#).unwrap();
 
@@ -54,41 +65,59 @@ impl CoolProxy {
 
         let re = Regex::new(r#""(.*?[^\\])""#)?;
         let mut result = Vec::new();
+        report.rows = ips.len();
 
         for (i, ip_script) in ips.iter().enumerate() {
-            if let Some(captures) = re.captures(ip_script) {
-                if let Some(encoded) = captures.get(1) {
-                     let rot13_decoded: String = encoded.as_str().chars().map(rot13).collect();
-                     if let Ok(decoded_bytes) = general_purpose::STANDARD.decode(rot13_decoded) {
-                         if let Ok(ip) = String::from_utf8(decoded_bytes) {
-                             result.push(ProxyMetadata {
-                                 addr: format!("{}:{}", ip, ports[i]),
-                                 kind: ProxyType::Http,
-                                 country: "Unknown".to_string(), // Still unknown for cool-proxy
-                             });
-                         }
-                     }
-                }
+            let Some(encoded) = re.captures(ip_script).and_then(|c| c.get(1)) else {
+                report.reject("no encoded address");
+                continue;
+            };
+            let rot13_decoded: String = encoded.as_str().chars().map(rot13).collect();
+            let Some(ip) = general_purpose::STANDARD
+                .decode(rot13_decoded)
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+            else {
+                report.reject("undecodable address");
+                continue;
+            };
+            match ProxyAddr::from_host_port(&ip, &ports[i]) {
+                // Still unknown country for cool-proxy
+                Ok(addr) => result.push(ProxyMetadata::new(addr, ProxyType::Http, Country::UNKNOWN)),
+                Err(_) => report.reject("invalid address"),
             }
         }
 
-        self.base.update_cache(result.clone());
         Ok(result)
     }
 }
 
+impl Default for CoolProxy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl Provider for CoolProxy {
     async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
-        self.load_internal().await
+        self.base.load(&self.url, Self::parse_with_report).await
     }
 
     fn name(&self) -> &'static str {
         "www.cool-proxy.net"
     }
 
-    fn set_proxy(&mut self, proxy: String) {
-        self.base.proxy_upstream = proxy;
+    fn set_proxy(&mut self, proxy: Proxy) {
+        self.base.proxy_upstream = Some(proxy);
+    }
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        self.base.report.take()
+    }
+
+    fn set_client_mode(&mut self, mode: ClientMode) {
+        self.base.client_mode = mode;
     }
 }
 
@@ -1,192 +1,383 @@
-use super::base::BaseProvider;
-use super::new_client;
+use super::base::{BaseProvider, ProviderOptions};
+use super::fixtures::ClientMode;
+use super::health::ParseReport;
+use super::js_expr::Scope;
 use crate::provider::Provider;
-use crate::proxy::{ProxyMetadata, ProxyType};
-use anyhow::{anyhow, Result};
+use crate::country::Country;
+use crate::proxy::{Anonymity, Proxy, ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
+use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
 use regex::Regex;
-use scraper::{Html, Selector};
-use std::collections::HashMap;
+use reqwest::Url;
+use log::debug;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+const CYBERSYNDROME_URL: &str = "https://www.cybersyndrome.net/";
+
+/// One of the site's lists; all share the ranking page's layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CybersyndromeList {
+    Ranking,          // Sorted by speed
+    Anonymous,        // Anonymous proxies only
+    Newest,           // Most recently found first
+    Country(Country), // Search results for one country
+}
+
+impl CybersyndromeList {
+    pub fn url(&self) -> String {
+        format!("{}{}", CYBERSYNDROME_URL, self.path())
+    }
+
+    /// The list's page on `site`, the site itself or a mirror. Any page there
+    /// works as well, e.g. `https://www.cybersyndrome.net/plr6.html`.
+    pub fn url_on(&self, site: &str) -> Result<String> {
+        Ok(Url::parse(site)?.join(&self.path())?.to_string())
+    }
+
+    fn path(&self) -> String {
+        match self {
+            CybersyndromeList::Ranking => "plr6.html".to_string(),
+            CybersyndromeList::Anonymous => "pla6.html".to_string(),
+            CybersyndromeList::Newest => "pld6.html".to_string(),
+            CybersyndromeList::Country(country) => {
+                format!("search.cgi?q={}", country.code().unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// `ranking`, `anonymous`, `newest`, or a country code or name.
+impl FromStr for CybersyndromeList {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ranking" => Ok(CybersyndromeList::Ranking),
+            "anonymous" => Ok(CybersyndromeList::Anonymous),
+            "newest" => Ok(CybersyndromeList::Newest),
+            _ => s
+                .parse()
+                .map(CybersyndromeList::Country)
+                .map_err(|_| anyhow!("unknown cybersyndrome list: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CybersyndromeList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for CybersyndromeList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            CybersyndromeList::Ranking => serializer.serialize_str("ranking"),
+            CybersyndromeList::Anonymous => serializer.serialize_str("anonymous"),
+            CybersyndromeList::Newest => serializer.serialize_str("newest"),
+            CybersyndromeList::Country(country) => country.serialize(serializer),
+        }
+    }
+}
 
 pub struct Cybersyndrome {
     base: BaseProvider,
+    site: String, // Site or mirror the lists' pages are resolved against
+    lists: Vec<CybersyndromeList>, // Ranking first; an address keeps its first listing
 }
 
 impl Cybersyndrome {
+    /// Fetches the ranking, anonymous and newest lists.
     pub fn new() -> Self {
         Self {
             base: BaseProvider::new(),
+            site: CYBERSYNDROME_URL.to_string(),
+            lists: Vec::new(),
         }
+        .with_lists(&[
+            CybersyndromeList::Ranking,
+            CybersyndromeList::Anonymous,
+            CybersyndromeList::Newest,
+        ])
     }
 
-    async fn load_internal(&mut self) -> Result<Vec<ProxyMetadata>> {
-        if !self.base.should_update() {
-            return Ok(self.base.cached_list());
-        }
+    pub fn with_lists(mut self, lists: &[CybersyndromeList]) -> Self {
+        self.lists = lists.to_vec();
+        // Stable, so the other lists keep their order
+        self.lists.sort_by_key(|list| *list != CybersyndromeList::Ranking);
+        self
+    }
 
-        let client = new_client(if self.base.proxy_upstream.is_empty() {
-            None
-        } else {
-            Some(&self.base.proxy_upstream)
-        })?;
+    /// A configured `url` is a mirror of the site; every list is fetched from it.
+    pub fn with_options(mut self, options: ProviderOptions) -> Self {
+        if let Some(url) = &options.url {
+            self.site = url.clone();
+        }
+        self.base.apply(&options);
+        self
+    }
 
-        // URL for the Speed Ranking list
-        let url = "https://www.cybersyndrome.net/plr6.html";
-        let resp = client.get(url).send().await?;
-        let body = resp.text().await?;
+    /// Proxies in a downloaded list page, decoded from its inline script.
+    pub fn parse(body: &str) -> Result<Vec<ProxyMetadata>> {
+        Self::parse_with_report(body, &mut ParseReport::new())
+    }
 
-        // 1. Parse Countries from HTML Table
-        // IDs are n1, n2, ... corresponds to index 0, 1...
-        let doc = Html::parse_document(&body);
+    /// `parse`, noting in `report` how many rows it saw and why any were dropped.
+    pub fn parse_with_report(body: &str, report: &mut ParseReport) -> Result<Vec<ProxyMetadata>> {
+        // 1. Read the table; the script fills in the address cell of each row
+        // <tr><td>Rank</td><td id="n1"></td><td>Host</td><td class="A">A</td><td>Country</td></tr>
+        // IDs n1, n2, ... correspond to script indexes 0, 1, ...
+        let doc = Html::parse_document(body);
         let tr_selector = Selector::parse("tr").unwrap();
         let td_selector = Selector::parse("td").unwrap();
-        
-        let mut country_map: HashMap<usize, String> = HashMap::new();
 
+        let mut table: HashMap<usize, Row> = HashMap::new();
         for tr in doc.select(&tr_selector) {
             let tds: Vec<_> = tr.select(&td_selector).collect();
-            // Expected row: <td>Rank</td><td id="n1"></td>...<td>Country</td>
-            // Length should be at least 5.
-            if tds.len() >= 5 {
-                // Check 2nd td for id
-                if let Some(id_attr) = tds[1].value().attr("id") {
-                    if id_attr.starts_with('n') {
-                        if let Ok(idx) = id_attr[1..].parse::<usize>() {
-                             // Country is in the 5th td (index 4)
-                             let country = tds[4].text().collect::<Vec<_>>().join("").trim().to_string();
-                             // ID n1 corresponds to index 0
-                             if idx > 0 {
-                                 country_map.insert(idx - 1, country);
-                             }
-                        }
-                    }
-                }
+            if tds.len() < 5 {
+                continue;
             }
+            let Some(idx) = tds[1]
+                .value()
+                .attr("id")
+                .and_then(|id| id.strip_prefix('n'))
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+            else {
+                continue;
+            };
+            let hostname = cell_text(&tds[2]);
+            table.insert(
+                idx - 1,
+                Row {
+                    rank: cell_text(&tds[0]).parse().ok(),
+                    hostname: (!hostname.is_empty()).then_some(hostname),
+                    anonymity: anonymity(&cell_text(&tds[3])),
+                    country: Country::parse(&cell_text(&tds[4])),
+                },
+            );
         }
 
-        // 2. Parse JS variables
-        // var as=[...];
-        // var ps=[...];
-        // var n=(...)%120;
-
-        let re_as = Regex::new(r"var\s+as\s*=\s*\[([^\]]+)\];")?;
-        let re_ps = Regex::new(r"var\s+ps\s*=\s*\[([^\]]+)\];")?;
-        let re_n = Regex::new(r"var\s+n\s*=\s*\(([^)]+)\)%120;")?;
-
-        let as_str = re_as
-            .captures(&body)
-            .and_then(|c| c.get(1))
-            .ok_or_else(|| anyhow!("Failed to find 'as' array"))?
-            .as_str();
-        
-        let ps_str = re_ps
-            .captures(&body)
-            .and_then(|c| c.get(1))
-            .ok_or_else(|| anyhow!("Failed to find 'ps' array"))?
-            .as_str();
-            
-        let n_expr = re_n
-            .captures(&body)
-            .and_then(|c| c.get(1))
-            .ok_or_else(|| anyhow!("Failed to find 'n' expression"))?
-            .as_str();
-
-        // Parse arrays
-        let mut as_vec: Vec<i32> = as_str
-            .split(',')
-            .map(|s| s.trim().parse().unwrap_or(0))
+        // 2. Decode the inline script, which shuffles the octets with
+        //   var as=[...];var ps=[...];var n=(...)%120;as=as.concat(as.splice(0,n));
+        // and prints `addrs[j]+":"+ps[j]`
+        let script = decode_script(body)?;
+        let mut as_vec: Vec<Option<u8>> = script
+            .addresses
+            .iter()
+            .map(|v| v.and_then(|v| u8::try_from(v).ok()))
             .collect();
-        
-        let ps_vec: Vec<i32> = ps_str
-            .split(',')
-            .map(|s| s.trim().parse().unwrap_or(0))
+        let ps_vec: Vec<Option<u16>> = script
+            .ports
+            .iter()
+            .map(|v| v.and_then(|v| u16::try_from(v).ok()))
             .collect();
 
-        // Calculate n
-        let n = eval_n(n_expr, &ps_vec)?;
-        let n = n % 120;
-
-        if n < as_vec.len() as i32 { // Valid n
-             as_vec.rotate_left(n as usize);
-        }
+        // `splice` clamps its count to the array
+        let n = script.rotation.clamp(0, as_vec.len() as i64) as usize;
+        as_vec.rotate_left(n);
 
         let mut proxies = Vec::new();
         let num_ips = as_vec.len() / 4;
+        if num_ips != ps_vec.len() {
+            report.warn(format!("{} addresses for {} ports", num_ips, ps_vec.len()));
+        }
+        let rows = proxies_len_limit(num_ips, ps_vec.len());
+        report.rows += rows;
 
-        for j in 0..proxies_len_limit(num_ips, ps_vec.len()) {
-            let p1 = as_vec[j * 4];
-            let p2 = as_vec[j * 4 + 1];
-            let p3 = as_vec[j * 4 + 2];
-            let p4 = as_vec[j * 4 + 3];
-            let port = ps_vec[j];
+        for j in 0..rows {
+            let octets: Option<Vec<u8>> = as_vec[j * 4..j * 4 + 4].iter().copied().collect();
+            let Some(octets) = octets else {
+                report.reject("unparsable octet");
+                continue;
+            };
+            let Some(port) = ps_vec[j] else {
+                report.reject("unparsable port");
+                continue;
+            };
             
-            let ip = format!("{}.{}.{}.{}", p1, p2, p3, p4);
-            let addr = format!("{}:{}", ip, port);
-
-            // Lookup country
-            let country = country_map.get(&j).cloned().unwrap_or_else(|| "Unknown".to_string());
+            let ip = format!("{}.{}.{}.{}", octets[0], octets[1], octets[2], octets[3]);
+            let addr = match ProxyAddr::from_host_port(&ip, &port.to_string()) {
+                Ok(addr) => addr,
+                Err(_) => {
+                    report.reject("invalid address");
+                    continue;
+                }
+            };
 
-            proxies.push(ProxyMetadata {
-                addr,
-                kind: ProxyType::Http, // Cybersyndrome lists HTTP proxies usually
-                country,
-            });
+            // Cybersyndrome lists HTTP proxies usually
+            let mut meta = ProxyMetadata::new(addr, ProxyType::Http, Country::UNKNOWN);
+            if let Some(row) = table.remove(&j) {
+                meta.country = row.country;
+                meta.hostname = row.hostname;
+                meta.anonymity = row.anonymity;
+                meta.rank = row.rank;
+            }
+            proxies.push(meta);
         }
 
-        self.base.update_cache(proxies.clone());
         Ok(proxies)
     }
 }
 
+struct Row {
+    rank: Option<usize>,
+    hostname: Option<String>,
+    anonymity: Option<Anonymity>,
+    country: Country,
+}
+
+fn cell_text(td: &ElementRef) -> String {
+    td.text().collect::<String>().trim().to_string()
+}
+
+// A: no proxy headers; B: proxy headers; C: a made-up client IP; D: the real one
+fn anonymity(class: &str) -> Option<Anonymity> {
+    match class {
+        "A" => Some(Anonymity::Elite),
+        "B" | "C" => Some(Anonymity::Anonymous),
+        "D" => Some(Anonymity::Transparent),
+        _ => None,
+    }
+}
+
 fn proxies_len_limit(ips: usize, ports: usize) -> usize {
     if ips < ports { ips } else { ports }
 }
 
-fn eval_n(expr: &str, ps: &[i32]) -> Result<i32> {
-    // Expression format: "123+456*ps[7]+890"
-    // Split by '+'
-    let parts: Vec<&str> = expr.split('+').collect();
-    let mut sum = 0;
-
-    for part in parts {
-        let part = part.trim();
-        if part.contains("*ps[") {
-             // e.g. "291*ps[22]"
-             let subparts: Vec<&str> = part.split('*').collect();
-             if subparts.len() != 2 { continue; }
-             let coeff: i32 = subparts[0].trim().parse().unwrap_or(0);
-             
-             // Extract index from "ps[22]"
-             let idx_str = subparts[1].trim();
-             // Removing "ps[" and "]"
-             let start = idx_str.find('[').unwrap_or(0) + 1;
-             let end = idx_str.find(']').unwrap_or(idx_str.len());
-             if start >= end { continue; }
-             
-             let idx: usize = idx_str[start..end].parse().unwrap_or(0);
-             let val = if idx < ps.len() { ps[idx] } else { 0 };
-             
-             sum += coeff * val;
-        } else {
-             // just a number
-             let val: i32 = part.parse().unwrap_or(0);
-             sum += val;
+struct Script {
+    addresses: Vec<Option<i64>>, // Octets, before rotating
+    ports: Vec<Option<i64>>,
+    rotation: i64, // Octets moved from the front to the back
+}
+
+/// Evaluate the declarations ahead of the `concat(splice(...))` rotation with
+/// `js_expr`, so changed constants or a different modulus still decode.
+fn decode_script(body: &str) -> Result<Script> {
+    let re_rotate = Regex::new(r"(\w+)\s*=\s*(\w+)\.concat\(\s*(\w+)\.splice\(\s*0\s*,\s*([^()]+?)\s*\)\s*\)")?;
+    let re_array = Regex::new(r"var\s+(\w+)\s*=\s*\[([^\]]*)\]")?;
+    let re_var = Regex::new(r"var\s+(\w+)\s*=\s*([^\[;][^;]*);")?;
+    let re_port = Regex::new(r#"[\"']:[\"']\s*\+\s*(\w+)\["#)?;
+
+    let rotate = re_rotate
+        .captures(body)
+        .ok_or_else(|| anyhow!("Failed to find the address rotation"))?;
+    let addresses = &rotate[2];
+    if rotate[1] != *addresses || rotate[3] != *addresses {
+        bail!("Rotation mixes arrays: {}", &rotate[0]);
+    }
+    let start = rotate.get(0).map_or(0, |m| m.start());
+    let declarations = &body[..start];
+    let script = &declarations[declarations.rfind("<script").unwrap_or(0)..];
+
+    let mut scope = Scope::new();
+    for caps in re_array.captures_iter(script) {
+        let values = caps[2].split(',').map(|s| s.trim().parse().ok()).collect();
+        scope.set_array(&caps[1], values);
+    }
+    // Scalars are evaluated in order; a failure only matters if it's used
+    let mut failed = HashMap::new();
+    for caps in re_var.captures_iter(script) {
+        match scope.eval(&caps[2]) {
+            Ok(value) => scope.set(&caps[1], value),
+            Err(e) => {
+                failed.insert(caps[1].to_string(), e);
+            }
         }
     }
-    Ok(sum)
+
+    let count = rotate[4].trim();
+    let rotation = scope.eval(count).map_err(|e| match failed.remove(count) {
+        Some(cause) => anyhow!("Failed to evaluate '{}': {}", count, cause),
+        None => anyhow!("Failed to evaluate rotation '{}': {}", count, e),
+    })?;
+
+    let ports = re_port
+        .captures(body)
+        .ok_or_else(|| anyhow!("Failed to find the port array"))?;
+    Ok(Script {
+        addresses: scope
+            .array(addresses)
+            .ok_or_else(|| anyhow!("Failed to find '{}' array", addresses))?
+            .to_vec(),
+        ports: scope
+            .array(&ports[1])
+            .ok_or_else(|| anyhow!("Failed to find '{}' array", &ports[1]))?
+            .to_vec(),
+        rotation,
+    })
+}
+
+impl Default for Cybersyndrome {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[async_trait]
 impl Provider for Cybersyndrome {
     async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
-        self.load_internal().await
+        if !self.base.should_update() {
+            return Ok(self.base.cached_list());
+        }
+
+        // One report for all pages; a failed page only fails the list if every page does
+        let mut report = ParseReport::new();
+        let mut seen = HashSet::new();
+        let mut proxies = Vec::new();
+        let mut last_error = None;
+        for list in &self.lists {
+            let url = list.url_on(&self.site)?;
+            let parsed = match self.base.fetch(&url).await {
+                Ok(body) => Self::parse_with_report(&body, &mut report),
+                Err(e) => Err(e),
+            };
+            match parsed {
+                Ok(mut page) => {
+                    debug!("{} has {} proxies", url, page.len());
+                    // Only the ranking's order says anything about quality
+                    if *list != CybersyndromeList::Ranking {
+                        page.iter_mut().for_each(|meta| meta.rank = None);
+                    }
+                    proxies.extend(page.into_iter().filter(|p| seen.insert(p.addr.clone())));
+                }
+                Err(e) => {
+                    report.warn(format!("{}: {}", url, e));
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        let result = match last_error {
+            Some(e) if proxies.is_empty() => Err(e),
+            _ => Ok(proxies),
+        };
+        let proxies = self.base.check(result, report)?;
+        self.base.update_cache(proxies.clone());
+        Ok(proxies)
     }
 
     fn name(&self) -> &'static str {
         "www.cybersyndrome.net"
     }
 
-    fn set_proxy(&mut self, proxy: String) {
-        self.base.proxy_upstream = proxy;
+    fn ranked(&self) -> bool {
+        self.lists.contains(&CybersyndromeList::Ranking) // Always fetched first
+    }
+
+    fn set_proxy(&mut self, proxy: Proxy) {
+        self.base.proxy_upstream = Some(proxy);
+    }
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        self.base.report.take()
+    }
+
+    fn set_client_mode(&mut self, mode: ClientMode) {
+        self.base.client_mode = mode;
     }
 }
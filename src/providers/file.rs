@@ -0,0 +1,88 @@
+use super::base::BaseProvider;
+use super::health::ParseReport;
+use super::list::{CsvColumns, JsonFields, ListFormat, ListParser};
+use crate::provider::Provider;
+use crate::proxy::{Proxy, ProxyMetadata, ProxyType};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Reads proxies from a local file, re-reading it whenever it changes on disk.
+pub struct FileProvider {
+    base: BaseProvider,
+    name: String,
+    path: PathBuf,
+    parser: ListParser,
+    modified: Option<SystemTime>,
+}
+
+impl FileProvider {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        Self {
+            base: BaseProvider::new(),
+            name: format!("file:{}", path.display()),
+            path,
+            parser: ListParser::default(),
+            modified: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: ListFormat) -> Self {
+        self.parser.format = format;
+        self
+    }
+
+    pub fn with_columns(mut self, columns: CsvColumns) -> Self {
+        self.parser.columns = columns;
+        self
+    }
+
+    pub fn with_fields(mut self, fields: JsonFields) -> Self {
+        self.parser.fields = fields;
+        self
+    }
+
+    pub fn with_default_kind(mut self, kind: ProxyType) -> Self {
+        self.parser.default_kind = kind;
+        self
+    }
+
+    fn load_internal(&mut self) -> Result<Vec<ProxyMetadata>> {
+        let modified = std::fs::metadata(&self.path)?.modified()?;
+        if self.base.last_update.is_some() && self.modified == Some(modified) {
+            return Ok(self.base.cached_list());
+        }
+
+        let data = std::fs::read_to_string(&self.path)?;
+        let extension = self.path.extension().and_then(|e| e.to_str());
+        let mut report = ParseReport::new();
+        let result = self.parser.parse_with_report(&data, extension, &mut report);
+        let proxies = self.base.check(result, report)?;
+        info!("read {} proxies from {}", proxies.len(), self.path.display());
+
+        self.modified = Some(modified);
+        self.base.update_cache(proxies.clone());
+        Ok(proxies)
+    }
+}
+
+#[async_trait]
+impl Provider for FileProvider {
+    async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
+        self.load_internal()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Local files need no upstream
+    fn set_proxy(&mut self, _proxy: Proxy) {}
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        self.base.report.take()
+    }
+}
@@ -0,0 +1,113 @@
+use super::registry::ProviderRegistry;
+use crate::configuration::Settings;
+use crate::provider::Provider;
+use anyhow::{anyhow, Result};
+use log::info;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How a provider's HTTP client treats requests, set per provider through
+/// `ProviderOptions::with_client_mode` or `Provider::set_client_mode`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ClientMode {
+    #[default]
+    Live,
+    Record(PathBuf), // Fetch as usual and save every body into a `FixtureStore`
+    Replay(PathBuf), // Answer from a `FixtureStore` without touching the network
+}
+
+/// Raw provider responses on disk, one directory per URL holding a file per
+/// capture named by its Unix timestamp and a counter for captures within the
+/// same second: `<dir>/<url slug>/<secs>-<n>.txt`.
+#[derive(Debug, Clone)]
+pub struct FixtureStore {
+    dir: PathBuf,
+}
+
+impl FixtureStore {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Store `body` as the newest capture of `url`.
+    pub fn save(&self, url: &str, body: &str) -> Result<PathBuf> {
+        let dir = self.dir.join(slug(url));
+        std::fs::create_dir_all(&dir)?;
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        // `create_new`, so concurrent captures never overwrite each other
+        for n in 0.. {
+            let path = dir.join(format!("{}-{}.txt", secs, n));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(body.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        unreachable!()
+    }
+
+    /// Path of the newest capture of `url`.
+    pub fn latest(&self, url: &str) -> Result<PathBuf> {
+        let dir = self.dir.join(slug(url));
+        let newest = std::fs::read_dir(&dir)
+            .map_err(|e| anyhow!("no fixture for {} in {}: {}", url, self.dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter_map(|path| Some((capture_order(&path)?, path)))
+            .max_by_key(|(order, _)| *order);
+        newest
+            .map(|(_, path)| path)
+            .ok_or_else(|| anyhow!("no fixture for {} in {}", url, dir.display()))
+    }
+
+    /// Body of the newest capture of `url`.
+    pub fn load(&self, url: &str) -> Result<String> {
+        Ok(std::fs::read_to_string(self.latest(url)?)?)
+    }
+}
+
+// `<secs>-<n>.txt`, or `<secs>.txt` as written before the counter, to `(secs, n)`
+fn capture_order(path: &Path) -> Option<(u64, u64)> {
+    let stem = path.file_stem()?.to_str()?;
+    match stem.split_once('-') {
+        Some((secs, n)) => Some((secs.parse().ok()?, n.parse().ok()?)),
+        None => Some((stem.parse().ok()?, 0)),
+    }
+}
+
+// `https://api.example.com/v2/?a=b` -> `api.example.com_v2__a_b`
+fn slug(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let slug: String = rest
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-') { c } else { '_' })
+        .take(150)
+        .collect();
+    if slug.is_empty() { "_".to_string() } else { slug }
+}
+
+/// Fetch every provider enabled in `settings` once, saving the raw responses
+/// under `dir`. Returns each provider's name with its proxy count or error.
+pub async fn record<P: AsRef<Path>>(
+    settings: &Settings,
+    registry: &ProviderRegistry,
+    dir: P,
+) -> Vec<(String, Result<usize>)> {
+    let mut results = Vec::new();
+    for mut provider in registry.build(settings) {
+        provider.set_client_mode(ClientMode::Record(dir.as_ref().to_path_buf()));
+        let result = provider.list().await.map(|proxies| proxies.len());
+        if let Ok(count) = &result {
+            info!("recorded {} ({} proxies)", provider.name(), count);
+        }
+        results.push((provider.name().to_string(), result));
+    }
+    results
+}
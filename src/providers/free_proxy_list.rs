@@ -1,92 +1,270 @@
-use super::base::BaseProvider;
-use super::new_client;
+use super::base::{BaseProvider, ProviderOptions};
+use super::fixtures::ClientMode;
+use super::health::ParseReport;
 use crate::provider::Provider;
-use crate::proxy::{ProxyMetadata, ProxyType};
+use crate::country::Country;
+use crate::proxy::{Anonymity, Proxy, ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
+use std::time::{Duration, SystemTime};
 
-const FREE_PROXY_LIST_URL: &str = "https://free-proxy-list.net/";
+/// free-proxy-list.net and the sister sites sharing its table layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreeProxyListSite {
+    FreeProxyList,
+    SslProxies, // HTTPS-capable proxies only
+    UsProxy,    // Proxies in the US only
+    SocksProxy, // SOCKS proxies, with a version column
+}
+
+impl FreeProxyListSite {
+    pub fn url(&self) -> &'static str {
+        match self {
+            FreeProxyListSite::FreeProxyList => "https://free-proxy-list.net/",
+            FreeProxyListSite::SslProxies => "https://www.sslproxies.org/",
+            FreeProxyListSite::UsProxy => "https://www.us-proxy.org/",
+            FreeProxyListSite::SocksProxy => "https://www.socks-proxy.net/",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FreeProxyListSite::FreeProxyList => "free-proxy-list.net",
+            FreeProxyListSite::SslProxies => "sslproxies.org",
+            FreeProxyListSite::UsProxy => "us-proxy.org",
+            FreeProxyListSite::SocksProxy => "socks-proxy.net",
+        }
+    }
+}
 
 pub struct FreeProxyList {
     base: BaseProvider,
+    site: FreeProxyListSite,
+    url: String,
 }
 
 impl FreeProxyList {
     pub fn new() -> Self {
+        Self::for_site(FreeProxyListSite::FreeProxyList)
+    }
+
+    pub fn for_site(site: FreeProxyListSite) -> Self {
+        let mut base = BaseProvider::new();
+        base.headers.insert("Accept-Language".to_string(), "en-US,en;q=0.8".to_string());
+        base.headers.insert("User-Agent".to_string(), "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.114 Safari/537.36".to_string());
         Self {
-            base: BaseProvider::new(),
+            base,
+            site,
+            url: site.url().to_string(),
         }
     }
 
-    async fn load_internal(&mut self) -> Result<Vec<ProxyMetadata>> {
-        if !self.base.should_update() {
-             return Ok(self.base.cached_list());
+    pub fn with_options(mut self, options: ProviderOptions) -> Self {
+        if let Some(url) = &options.url {
+            self.url = url.clone();
         }
+        self.base.apply(&options);
+        self
+    }
 
-        let client = new_client(if self.base.proxy_upstream.is_empty() { None } else { Some(&self.base.proxy_upstream) })?;
-        
-        let resp = client.get(FREE_PROXY_LIST_URL)
-            .header("Accept-Language", "en-US,en;q=0.8")
-            .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.114 Safari/537.36")
-            .send().await?;
-            
-        let body = resp.text().await?;
-
-        let doc = Html::parse_document(&body);
-        let row_selector = Selector::parse(r#"#list table tbody tr"#).unwrap();
-        let row_selector_alt = Selector::parse(r#"table tbody tr"#).unwrap();
-        
-        let mut rows: Vec<_> = doc.select(&row_selector).collect();
-        if rows.is_empty() {
-             rows = doc.select(&row_selector_alt).collect();
-        }
+    /// Proxies in a downloaded list page of any of the sites.
+    pub fn parse(body: &str) -> Result<Vec<ProxyMetadata>> {
+        Self::parse_with_report(body, &mut ParseReport::new())
+    }
 
-        let mut result = Vec::new();
+    /// `parse`, noting in `report` how many rows it saw and why any were dropped.
+    pub fn parse_with_report(body: &str, report: &mut ParseReport) -> Result<Vec<ProxyMetadata>> {
+        let doc = Html::parse_document(body);
+        let table_selector = Selector::parse("#list table").unwrap();
+        let table_selector_alt = Selector::parse("table").unwrap();
+        let row_selector = Selector::parse("tbody tr").unwrap();
+        let th_selector = Selector::parse("thead th").unwrap();
         let td_selector = Selector::parse("td").unwrap();
 
+        let mut table = doc.select(&table_selector).next();
+        if table.is_none() {
+            table = doc
+                .select(&table_selector_alt)
+                .find(|t| t.select(&row_selector).next().is_some());
+            if table.is_some() {
+                report.warn("no `#list` table, read any table instead".to_string());
+            }
+        }
+        let Some(table) = table else {
+            return Err(anyhow!("proxies not found"));
+        };
+
+        // The sites order their columns differently, so go by the header
+        let headers: Vec<_> = table.select(&th_selector).map(|th| cell_text(&th)).collect();
+        let columns = Columns::from_headers(&headers).unwrap_or_else(|| {
+            report.warn(format!("unknown header {:?}, assuming the default columns", headers));
+            Columns::default()
+        });
+
+        let rows: Vec<_> = table.select(&row_selector).collect();
+        report.rows = rows.len();
+        let now = SystemTime::now();
+
+        let mut result = Vec::new();
         for row in rows {
-            let cols: Vec<_> = row.select(&td_selector).collect();
-            if cols.len() >= 8 {
-                let ip = cols[0].inner_html();
-                let port = cols[1].inner_html();
-                let country_code = cols[2].inner_html(); // 3rd column: Code
-                let https = cols[6].inner_html();       // 7th column: Https (yes/no)
-
-                let kind = if https.to_lowercase() == "yes" {
+            let cols: Vec<_> = row.select(&td_selector).map(|td| cell_text(&td)).collect();
+            let col = |idx: Option<usize>| idx.and_then(|i| cols.get(i)).map(String::as_str);
+            let (Some(ip), Some(port)) = (col(Some(columns.ip)), col(Some(columns.port))) else {
+                report.reject("too few columns");
+                continue;
+            };
+            let addr = match ProxyAddr::from_host_port(ip, port) {
+                Ok(addr) => addr,
+                Err(_) => {
+                    report.reject("invalid address");
+                    continue;
+                }
+            };
+
+            // A SOCKS version wins over the Https column
+            let kind = match col(columns.version).map(str::parse::<ProxyType>) {
+                Some(Ok(kind)) => kind,
+                Some(Err(_)) => {
+                    report.reject("unknown version");
+                    continue;
+                }
+                None if col(columns.https).is_some_and(|h| h.eq_ignore_ascii_case("yes")) => {
                     ProxyType::Https
-                } else {
-                    ProxyType::Http
-                };
-
-                result.push(ProxyMetadata {
-                    addr: format!("{}:{}", ip, port),
-                    kind,
-                    country: country_code,
-                });
-            }
+                }
+                None => ProxyType::Http,
+            };
+
+            let country = col(columns.code).map(Country::parse).unwrap_or_default();
+            let mut meta = ProxyMetadata::new(addr, kind, country);
+            meta.anonymity = col(columns.anonymity).and_then(anonymity);
+            meta.google = col(columns.google).and_then(yes_no);
+            meta.last_checked = col(columns.last_checked)
+                .and_then(parse_age)
+                .and_then(|age| now.checked_sub(age));
+            result.push(meta);
         }
 
         if result.is_empty() {
             return Err(anyhow!("proxies not found"));
         }
 
-        self.base.update_cache(result.clone());
         Ok(result)
     }
 }
 
+/// Indexes of the table's columns.
+struct Columns {
+    ip: usize,
+    port: usize,
+    code: Option<usize>,
+    anonymity: Option<usize>,
+    google: Option<usize>,
+    https: Option<usize>,
+    version: Option<usize>,
+    last_checked: Option<usize>,
+}
+
+impl Columns {
+    fn from_headers(headers: &[String]) -> Option<Self> {
+        let find = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+        Some(Self {
+            ip: find("IP Address")?,
+            port: find("Port")?,
+            code: find("Code"),
+            anonymity: find("Anonymity"),
+            google: find("Google"),
+            https: find("Https"),
+            version: find("Version"),
+            last_checked: find("Last Checked"),
+        })
+    }
+}
+
+// free-proxy-list.net's layout
+impl Default for Columns {
+    fn default() -> Self {
+        Self {
+            ip: 0,
+            port: 1,
+            code: Some(2),
+            anonymity: Some(4),
+            google: Some(5),
+            https: Some(6),
+            version: None,
+            last_checked: Some(7),
+        }
+    }
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    cell.text().collect::<String>().trim().to_string()
+}
+
+fn anonymity(s: &str) -> Option<Anonymity> {
+    match s.to_ascii_lowercase().as_str() {
+        "elite proxy" | "elite" => Some(Anonymity::Elite),
+        "anonymous" => Some(Anonymity::Anonymous),
+        "transparent" => Some(Anonymity::Transparent),
+        _ => None,
+    }
+}
+
+fn yes_no(s: &str) -> Option<bool> {
+    match s.to_ascii_lowercase().as_str() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+// "1 hour 5 mins ago", "30 secs ago"
+fn parse_age(s: &str) -> Option<Duration> {
+    let words: Vec<_> = s.split_whitespace().filter(|w| *w != "ago").collect();
+    if words.is_empty() || words.len() % 2 != 0 {
+        return None;
+    }
+    let mut secs: u64 = 0;
+    for pair in words.chunks(2) {
+        let n: u64 = pair[0].parse().ok()?;
+        let unit = match pair[1].trim_end_matches('s') {
+            "sec" | "second" => 1,
+            "min" | "minute" => 60,
+            "hour" => 60 * 60,
+            "day" => 24 * 60 * 60,
+            _ => return None,
+        };
+        secs = n.checked_mul(unit).and_then(|n| n.checked_add(secs))?;
+    }
+    Some(Duration::from_secs(secs))
+}
+
+impl Default for FreeProxyList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl Provider for FreeProxyList {
     async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
-        self.load_internal().await
+        self.base.load(&self.url, Self::parse_with_report).await
     }
 
     fn name(&self) -> &'static str {
-        "free-proxy-list.net"
+        self.site.name()
+    }
+
+    fn set_proxy(&mut self, proxy: Proxy) {
+        self.base.proxy_upstream = Some(proxy);
+    }
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        self.base.report.take()
     }
 
-    fn set_proxy(&mut self, proxy: String) {
-        self.base.proxy_upstream = proxy;
+    fn set_client_mode(&mut self, mode: ClientMode) {
+        self.base.client_mode = mode;
     }
 }
@@ -0,0 +1,162 @@
+use crate::proxy::ProxyMetadata;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+
+/// What one parse of a freshly fetched page saw.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    pub rows: usize,                       // Candidate rows or entries on the page
+    pub accepted: usize,                   // Proxies left after `validate`
+    pub rejected: BTreeMap<String, usize>, // Rows dropped, by reason
+    pub warnings: Vec<String>,             // Layout oddities that didn't stop the parse
+    pub error: Option<String>,             // Why the whole parse failed
+    pub previous: Option<usize>,           // Yield of the last successful parse
+}
+
+impl ParseReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reject(&mut self, reason: &str) {
+        *self.rejected.entry(reason.to_string()).or_default() += 1;
+    }
+
+    pub fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    pub fn rejected_total(&self) -> usize {
+        self.rejected.values().sum()
+    }
+
+    /// Drop entries no parser should produce (port 0, unspecified or broadcast
+    /// IPs), counting them as rejected, and record the yield.
+    pub fn validate(&mut self, proxies: Vec<ProxyMetadata>) -> Vec<ProxyMetadata> {
+        let mut valid = Vec::with_capacity(proxies.len());
+        for meta in proxies {
+            let bogus_ip = meta.addr.ip().is_some_and(|ip| match ip {
+                IpAddr::V4(v4) => v4.is_unspecified() || v4.is_broadcast(),
+                IpAddr::V6(v6) => v6.is_unspecified(),
+            });
+            if meta.addr.port == 0 || bogus_ip {
+                self.reject("invalid address");
+            } else {
+                valid.push(meta);
+            }
+        }
+        self.accepted = valid.len();
+        self.rows = self.rows.max(self.accepted + self.rejected_total());
+        valid
+    }
+
+    /// Whether this parse looks like the site changed under the parser.
+    pub fn drift(&self, policy: &DriftPolicy) -> Option<DriftReason> {
+        if let Some(error) = &self.error {
+            return Some(DriftReason::ParseFailed(error.clone()));
+        }
+        if let Some(previous) = self.previous {
+            if previous >= policy.min_previous
+                && (self.accepted as f64) < previous as f64 * policy.collapse_ratio
+            {
+                return Some(DriftReason::YieldCollapsed {
+                    previous,
+                    current: self.accepted,
+                });
+            }
+        }
+        let rejected = self.rejected_total();
+        if self.rows > 0 && rejected as f64 > self.rows as f64 * policy.max_rejected_ratio {
+            return Some(DriftReason::MostlyRejected {
+                rejected,
+                rows: self.rows,
+            });
+        }
+        None
+    }
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} rows, {} accepted", self.rows, self.accepted)?;
+        for (reason, count) in &self.rejected {
+            write!(f, ", {} {}", count, reason)?;
+        }
+        if let Some(previous) = self.previous {
+            write!(f, " (previously {})", previous)?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, ", failed: {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Thresholds for raising a `DriftEvent`.
+#[derive(Debug, Clone)]
+pub struct DriftPolicy {
+    pub min_previous: usize,     // Smaller lists are too noisy to compare
+    pub collapse_ratio: f64,     // Yield below this share of the previous one
+    pub max_rejected_ratio: f64, // Share of rows rejected
+}
+
+impl Default for DriftPolicy {
+    fn default() -> Self {
+        Self {
+            min_previous: 10,
+            collapse_ratio: 0.2,
+            max_rejected_ratio: 0.5,
+        }
+    }
+}
+
+impl DriftPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_min_previous(mut self, min_previous: usize) -> Self {
+        self.min_previous = min_previous;
+        self
+    }
+
+    pub fn with_collapse_ratio(mut self, ratio: f64) -> Self {
+        self.collapse_ratio = ratio;
+        self
+    }
+
+    pub fn with_max_rejected_ratio(mut self, ratio: f64) -> Self {
+        self.max_rejected_ratio = ratio;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriftReason {
+    ParseFailed(String),
+    YieldCollapsed { previous: usize, current: usize },
+    MostlyRejected { rejected: usize, rows: usize },
+}
+
+impl fmt::Display for DriftReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DriftReason::ParseFailed(e) => write!(f, "parse failed: {}", e),
+            DriftReason::YieldCollapsed { previous, current } => {
+                write!(f, "yield dropped from {} to {}", previous, current)
+            }
+            DriftReason::MostlyRejected { rejected, rows } => {
+                write!(f, "{} of {} rows rejected", rejected, rows)
+            }
+        }
+    }
+}
+
+/// A provider's parser probably no longer matches its site.
+#[derive(Debug, Clone)]
+pub struct DriftEvent {
+    pub provider: String,
+    pub reason: DriftReason,
+    pub report: ParseReport,
+}
@@ -0,0 +1,243 @@
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+use std::fmt;
+
+// Limits keeping hostile pages from making evaluation expensive
+const MAX_LEN: usize = 4096;
+const MAX_DEPTH: usize = 64;
+
+/// Integer arithmetic over the variables a page's inline script declares, for
+/// decoding obfuscated lists without running any JavaScript.
+///
+/// Supports `+ - * / %`, unary minus, parentheses, numbers, scalar names and
+/// `array[index]`. Nothing else is accepted, and every failure (unknown name,
+/// index out of range, overflow, division by zero or with a remainder) is an
+/// error rather than a guess.
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    arrays: HashMap<String, Vec<Option<i64>>>, // `None` for entries that weren't integers
+    values: HashMap<String, i64>,
+}
+
+impl Scope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_array(&mut self, name: &str, values: Vec<Option<i64>>) {
+        self.arrays.insert(name.to_string(), values);
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn array(&self, name: &str) -> Option<&[Option<i64>]> {
+        self.arrays.get(name).map(Vec::as_slice)
+    }
+
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.values.get(name).copied()
+    }
+
+    pub fn eval(&self, expr: &str) -> Result<i64> {
+        if expr.len() > MAX_LEN {
+            bail!("expression longer than {} bytes", MAX_LEN);
+        }
+        let tokens = tokenize(expr)?;
+        let mut parser = Parser {
+            scope: self,
+            tokens: &tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.expr()?;
+        match tokens.get(parser.pos) {
+            None => Ok(value),
+            Some(token) => Err(anyhow!("unexpected {} in `{}`", token, expr)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(i64),
+    Name(String),
+    Sym(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Num(n) => write!(f, "`{}`", n),
+            Token::Name(name) => write!(f, "`{}`", name),
+            Token::Sym(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() {
+            let mut end = start;
+            while let Some(&(i, d)) = chars.peek() {
+                if !d.is_ascii_digit() {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+            }
+            let digits = &expr[start..end];
+            let n = digits.parse().map_err(|_| anyhow!("number {} out of range", digits))?;
+            tokens.push(Token::Num(n));
+        } else if c.is_ascii_alphabetic() || c == '_' || c == '$' {
+            let mut end = start;
+            while let Some(&(i, d)) = chars.peek() {
+                if !(d.is_ascii_alphanumeric() || d == '_' || d == '$') {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+            }
+            tokens.push(Token::Name(expr[start..end].to_string()));
+        } else if "+-*/%()[]".contains(c) {
+            tokens.push(Token::Sym(c));
+            chars.next();
+        } else {
+            bail!("unsupported character `{}` at {} in `{}`", c, start, expr);
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    scope: &'a Scope,
+    tokens: &'a [Token],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek_sym(&self) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Sym(c)) => Some(*c),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, sym: char) -> Result<()> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Sym(c)) if *c == sym => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(token) => Err(anyhow!("expected `{}`, found {}", sym, token)),
+            None => Err(anyhow!("expected `{}`, found the end", sym)),
+        }
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            bail!("expression nested deeper than {}", MAX_DEPTH);
+        }
+        Ok(())
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<i64> {
+        self.enter()?;
+        let mut value = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek_sym() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = match op {
+                '+' => value.checked_add(rhs),
+                _ => value.checked_sub(rhs),
+            }
+            .ok_or_else(|| anyhow!("overflow"))?;
+        }
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<i64> {
+        let mut value = self.unary()?;
+        while let Some(op @ ('*' | '/' | '%')) = self.peek_sym() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            if op == '*' {
+                value = value.checked_mul(rhs).ok_or_else(|| anyhow!("overflow"))?;
+                continue;
+            }
+            if rhs == 0 {
+                bail!("division by zero");
+            }
+            let rem = value.checked_rem(rhs).ok_or_else(|| anyhow!("overflow"))?;
+            value = match op {
+                '/' if rem != 0 => bail!("{} / {} is not an integer", value, rhs),
+                '/' => value / rhs,
+                _ => rem, // Sign follows the dividend, as in JavaScript
+            };
+        }
+        Ok(value)
+    }
+
+    // unary := ('-' | '+') unary | primary
+    fn unary(&mut self) -> Result<i64> {
+        match self.peek_sym() {
+            Some(op @ ('-' | '+')) => {
+                self.pos += 1;
+                self.enter()?;
+                let value = self.unary()?;
+                self.depth -= 1;
+                match op {
+                    '-' => value.checked_neg().ok_or_else(|| anyhow!("overflow")),
+                    _ => Ok(value),
+                }
+            }
+            _ => self.primary(),
+        }
+    }
+
+    // primary := number | '(' expr ')' | name | name '[' expr ']'
+    fn primary(&mut self) -> Result<i64> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| anyhow!("unexpected end of expression"))?;
+        self.pos += 1;
+        match token {
+            Token::Num(n) => Ok(*n),
+            Token::Sym('(') => {
+                let value = self.expr()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Token::Name(name) if self.peek_sym() == Some('[') => {
+                self.pos += 1;
+                let index = self.expr()?;
+                self.expect(']')?;
+                let array = self
+                    .scope
+                    .array(name)
+                    .ok_or_else(|| anyhow!("unknown array `{}`", name))?;
+                let entry = usize::try_from(index)
+                    .ok()
+                    .and_then(|i| array.get(i))
+                    .ok_or_else(|| anyhow!("{}[{}] is out of range ({} entries)", name, index, array.len()))?;
+                entry.ok_or_else(|| anyhow!("{}[{}] is not an integer", name, index))
+            }
+            Token::Name(name) => self
+                .scope
+                .get(name)
+                .ok_or_else(|| anyhow!("unknown variable `{}`", name)),
+            token => Err(anyhow!("unexpected {}", token)),
+        }
+    }
+}
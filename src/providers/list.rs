@@ -0,0 +1,329 @@
+use super::health::ParseReport;
+use crate::country::Country;
+use crate::proxy::{ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
+use anyhow::{anyhow, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Layout of a downloaded or local proxy list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListFormat {
+    #[default]
+    Auto, // Guess from the extension, then the contents
+    #[serde(alias = "lines")]
+    Plain,       // One `ip:port` or `scheme://[user:pass@]host:port` per line
+    Csv,         // Header row, columns named by `CsvColumns`
+    Json,        // Array of URL strings or objects, located by `JsonFields`
+    Proxychains, // `type host port [user pass]` lines under `[ProxyList]`
+}
+
+/// CSV header names to read each field from, matched case-insensitively. When
+/// the `address` column is present it takes precedence over `host`/`port`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvColumns {
+    pub address: String,
+    pub host: String,
+    pub port: String,
+    pub kind: String,
+    pub country: String,
+    pub username: String,
+    pub password: String,
+}
+
+// Matches the columns written by `export::to_csv`
+impl Default for CsvColumns {
+    fn default() -> Self {
+        Self {
+            address: "url".to_string(),
+            host: "host".to_string(),
+            port: "port".to_string(),
+            kind: "type".to_string(),
+            country: "country".to_string(),
+            username: "username".to_string(),
+            password: "password".to_string(),
+        }
+    }
+}
+
+/// Dot-separated paths (`data.items`, `protocols.0`) into a JSON document. Unset
+/// fields fall back to common key names (`host`/`ip`, `port`, `type`/`protocol`,
+/// ...) and to the `addr` object written by `export::to_json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonFields {
+    pub items: Option<String>, // Path to the array of proxies; the root when unset
+    pub address: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<String>,
+    pub kind: Option<String>,
+    pub country: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// Turns list text in any `ListFormat` into proxy entries.
+#[derive(Debug, Clone)]
+pub struct ListParser {
+    pub format: ListFormat,
+    pub columns: CsvColumns,
+    pub fields: JsonFields,
+    pub default_kind: ProxyType,  // For entries that don't state a type
+    pub default_country: Country, // For entries that don't state a country
+}
+
+impl Default for ListParser {
+    fn default() -> Self {
+        Self {
+            format: ListFormat::Auto,
+            columns: CsvColumns::default(),
+            fields: JsonFields::default(),
+            default_kind: ProxyType::Http,
+            default_country: Country::UNKNOWN,
+        }
+    }
+}
+
+impl ListParser {
+    /// Parse `data`; `extension` (of the file or URL path) helps `Auto` decide.
+    pub fn parse(&self, data: &str, extension: Option<&str>) -> Result<Vec<ProxyMetadata>> {
+        self.parse_with_report(data, extension, &mut ParseReport::new())
+    }
+
+    /// `parse`, noting in `report` how many entries it saw and why any were dropped.
+    pub fn parse_with_report(
+        &self,
+        data: &str,
+        extension: Option<&str>,
+        report: &mut ParseReport,
+    ) -> Result<Vec<ProxyMetadata>> {
+        match self.detect_format(data, extension) {
+            ListFormat::Csv => self.parse_csv(data, report),
+            ListFormat::Json => self.parse_json(data, report),
+            ListFormat::Proxychains => Ok(self.parse_proxychains(data, report)),
+            ListFormat::Plain | ListFormat::Auto => Ok(self.parse_plain(data, report)),
+        }
+    }
+
+    fn detect_format(&self, data: &str, extension: Option<&str>) -> ListFormat {
+        if self.format != ListFormat::Auto {
+            return self.format;
+        }
+        match extension.unwrap_or_default().to_ascii_lowercase().as_str() {
+            "csv" => ListFormat::Csv,
+            "json" => ListFormat::Json,
+            _ if data.lines().any(|l| l.trim().eq_ignore_ascii_case("[ProxyList]")) => {
+                ListFormat::Proxychains
+            }
+            _ if serde_json::from_str::<Value>(data).is_ok_and(|v| v.is_array() || v.is_object()) => {
+                ListFormat::Json
+            }
+            _ => ListFormat::Plain,
+        }
+    }
+
+    fn parse_plain(&self, data: &str, report: &mut ParseReport) -> Vec<ProxyMetadata> {
+        data.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .filter_map(|line| {
+                report.rows += 1;
+                self.entry(line, None, None, None, report)
+            })
+            .collect()
+    }
+
+    fn parse_csv(&self, data: &str, report: &mut ParseReport) -> Result<Vec<ProxyMetadata>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(data.as_bytes());
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+
+        let c = &self.columns;
+        let (address, host, port) = (column(&c.address), column(&c.host), column(&c.port));
+        let (kind, country) = (column(&c.kind), column(&c.country));
+        let (username, password) = (column(&c.username), column(&c.password));
+        if address.is_none() && (host.is_none() || port.is_none()) {
+            return Err(anyhow!("CSV has neither an `{}` column nor `{}` and `{}`", c.address, c.host, c.port));
+        }
+
+        let mut proxies = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            report.rows += 1;
+            let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).filter(|s| !s.is_empty());
+
+            let addr = match field(address) {
+                Some(address) => address.to_string(),
+                None => match (field(host), field(port)) {
+                    (Some(host), Some(port)) => join_host_port(host, port),
+                    _ => {
+                        report.reject("missing address");
+                        continue;
+                    }
+                },
+            };
+            let credentials = field(username).map(|u| (u, field(password).unwrap_or_default()));
+            if let Some(meta) = self.entry(&addr, field(kind), field(country), credentials, report) {
+                proxies.push(meta);
+            }
+        }
+        Ok(proxies)
+    }
+
+    fn parse_json(&self, data: &str, report: &mut ParseReport) -> Result<Vec<ProxyMetadata>> {
+        let root: Value = serde_json::from_str(data)?;
+        let items = match &self.fields.items {
+            Some(path) => lookup(&root, path).ok_or_else(|| anyhow!("no `{}` in JSON", path))?,
+            None => &root,
+        };
+        let Value::Array(items) = items else {
+            return Err(anyhow!("expected a JSON array"));
+        };
+
+        let f = &self.fields;
+        let mut proxies = Vec::new();
+        for item in items {
+            report.rows += 1;
+            if let Value::String(url) = item {
+                proxies.extend(self.entry(url, None, None, None, report));
+                continue;
+            }
+            // A configured path wins, otherwise the first common key that is present
+            let text = |path: &Option<String>, keys: &[&str]| match path {
+                Some(path) => lookup(item, path).and_then(scalar),
+                None => keys.iter().find_map(|k| item.get(*k).and_then(scalar)),
+            };
+            let kind = text(&f.kind, &["kind", "type", "protocol"]);
+            let country = text(&f.country, &["country", "country_code"]);
+
+            // Output of `export::to_json`
+            if let (None, None, Some(addr)) = (&f.address, &f.host, item.get("addr")) {
+                match serde_json::from_value::<ProxyAddr>(addr.clone()) {
+                    Ok(addr) => proxies.push(self.metadata(addr, kind.as_deref(), None, country.as_deref())),
+                    Err(e) => {
+                        debug!("skipping JSON entry: {}", e);
+                        report.reject("invalid address");
+                    }
+                }
+                continue;
+            }
+
+            let address = text(&f.address, &["url", "proxy", "address"]);
+            let addr = match (address, text(&f.host, &["host", "ip"]), text(&f.port, &["port"])) {
+                (Some(url), _, _) => url,
+                (None, Some(host), Some(port)) => join_host_port(&host, &port),
+                _ => {
+                    report.reject("missing address");
+                    continue;
+                }
+            };
+            let username = text(&f.username, &["username", "user"]);
+            let password = text(&f.password, &["password", "pass"]);
+            let credentials = username
+                .as_deref()
+                .map(|u| (u, password.as_deref().unwrap_or_default()));
+            proxies.extend(self.entry(&addr, kind.as_deref(), country.as_deref(), credentials, report));
+        }
+        Ok(proxies)
+    }
+
+    fn parse_proxychains(&self, data: &str, report: &mut ParseReport) -> Vec<ProxyMetadata> {
+        let mut proxies = Vec::new();
+        let mut in_list = false;
+        for line in data.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.starts_with('[') {
+                in_list = line.eq_ignore_ascii_case("[ProxyList]");
+                continue;
+            }
+            if !in_list || line.is_empty() {
+                continue;
+            }
+
+            report.rows += 1;
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            let [kind, host, port, rest @ ..] = cols.as_slice() else {
+                report.reject("missing port");
+                continue;
+            };
+            let credentials = match rest {
+                [user, pass, ..] => Some((*user, *pass)),
+                _ => None,
+            };
+            let addr = join_host_port(host, port);
+            proxies.extend(self.entry(&addr, Some(kind), None, credentials, report));
+        }
+        proxies
+    }
+
+    // Shared by every format: parse the address, then apply the optional columns
+    fn entry(
+        &self,
+        addr: &str,
+        kind: Option<&str>,
+        country: Option<&str>,
+        credentials: Option<(&str, &str)>,
+        report: &mut ParseReport,
+    ) -> Option<ProxyMetadata> {
+        let (scheme_kind, mut addr) = match ProxyAddr::parse_url(addr) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                debug!("skipping list entry: {}", e);
+                report.reject("invalid address");
+                return None;
+            }
+        };
+        if let Some((username, password)) = credentials {
+            addr = addr.with_credentials(username, password);
+        }
+        Some(self.metadata(addr, kind, scheme_kind, country))
+    }
+
+    fn metadata(
+        &self,
+        addr: ProxyAddr,
+        kind: Option<&str>,
+        scheme_kind: Option<ProxyType>,
+        country: Option<&str>,
+    ) -> ProxyMetadata {
+        let kind = kind
+            .and_then(|k| k.parse().ok())
+            .or(scheme_kind)
+            .unwrap_or(self.default_kind.clone());
+        let country = country
+            .map(Country::parse)
+            .filter(Country::is_known)
+            .unwrap_or(self.default_country);
+        ProxyMetadata::new(addr, kind, country)
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |v, key| match key.parse::<usize>() {
+        Ok(idx) if v.is_array() => v.get(idx),
+        _ => v.get(key),
+    })
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+// Bare IPv6 hosts (as in proxychains or CSV columns) need brackets before parsing
+fn join_host_port(host: &str, port: &str) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
@@ -3,21 +3,70 @@ pub mod cool_proxy;
 pub mod free_proxy_list;
 pub mod cybersyndrome;
 pub mod proxyscrape;
+pub mod list;
+pub mod file;
+pub mod url_list;
+pub mod scrape;
+pub mod registry;
+pub mod fixtures;
+pub mod health;
+pub mod js_expr;
 
 // Common HTTP client construction if needed, or re-use logic
+use fixtures::{ClientMode, FixtureStore};
+use log::{debug, warn};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client;
+use std::collections::HashMap;
 use std::time::Duration;
 
-pub fn new_client(proxy: Option<&str>) -> anyhow::Result<Client> {
+/// Client for provider requests, recording or replaying responses as its
+/// `ClientMode` says.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    inner: Client,
+    mode: ClientMode,
+}
+
+impl HttpClient {
+    /// Body of `url`, failing on HTTP error statuses.
+    pub async fn get_text(&self, url: &str) -> anyhow::Result<String> {
+        if let ClientMode::Replay(dir) = &self.mode {
+            return FixtureStore::new(dir).load(url);
+        }
+
+        let resp = self.inner.get(url).send().await?.error_for_status()?;
+        let body = resp.text().await?;
+        if let ClientMode::Record(dir) = &self.mode {
+            match FixtureStore::new(dir).save(url, &body) {
+                Ok(path) => debug!("recorded {} to {}", url, path.display()),
+                Err(e) => warn!("failed to record {}: {}", url, e),
+            }
+        }
+        Ok(body)
+    }
+}
+
+pub fn new_client(
+    proxy: Option<reqwest::Proxy>,
+    headers: &HashMap<String, String>,
+    mode: &ClientMode,
+) -> anyhow::Result<HttpClient> {
+    let mut default_headers = HeaderMap::new();
+    for (name, value) in headers {
+        default_headers.insert(HeaderName::try_from(name.as_str())?, HeaderValue::try_from(value.as_str())?);
+    }
+
     let mut builder = Client::builder()
-        .timeout(Duration::from_secs(10));
+        .timeout(Duration::from_secs(10))
+        .default_headers(default_headers);
     
     if let Some(p) = proxy {
-         if !p.is_empty() {
-             let proxy_url = reqwest::Proxy::http(&format!("http://{}", p))?;
-             builder = builder.proxy(proxy_url);
-         }
+         builder = builder.proxy(p);
     }
 
-    Ok(builder.build()?)
+    Ok(HttpClient {
+        inner: builder.build()?,
+        mode: mode.clone(),
+    })
 }
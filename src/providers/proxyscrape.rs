@@ -1,65 +1,83 @@
-use super::base::BaseProvider;
-use super::new_client;
+use super::base::{BaseProvider, ProviderOptions};
+use super::fixtures::ClientMode;
+use super::health::ParseReport;
 use crate::provider::Provider;
-use crate::proxy::{ProxyMetadata, ProxyType};
+use crate::country::Country;
+use crate::proxy::{Proxy, ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
 use anyhow::Result;
 use async_trait::async_trait;
 
+// ProxyScrape API for HTTP proxies
+const PROXYSCRAPE_URL: &str = "https://api.proxyscrape.com/v2/?request=getproxies&protocol=http&timeout=10000&country=all&ssl=all&anonymity=all";
+
 pub struct ProxyScrape {
     base: BaseProvider,
+    url: String,
 }
 
 impl ProxyScrape {
     pub fn new() -> Self {
         Self {
             base: BaseProvider::new(),
+            url: PROXYSCRAPE_URL.to_string(),
         }
     }
 
-    async fn load_internal(&mut self) -> Result<Vec<ProxyMetadata>> {
-        if !self.base.should_update() {
-            return Ok(self.base.cached_list());
+    pub fn with_options(mut self, options: ProviderOptions) -> Self {
+        if let Some(url) = &options.url {
+            self.url = url.clone();
         }
+        self.base.apply(&options);
+        self
+    }
 
-        let client = new_client(if self.base.proxy_upstream.is_empty() {
-            None
-        } else {
-            Some(&self.base.proxy_upstream)
-        })?;
-
-        // ProxyScrape API for HTTP proxies
-        let url = "https://api.proxyscrape.com/v2/?request=getproxies&protocol=http&timeout=10000&country=all&ssl=all&anonymity=all";
-        let resp = client.get(url).send().await?;
-        let body = resp.text().await?;
+    /// Proxies in a downloaded API response: `ip:port` or `[v6]:port` lines.
+    pub fn parse(body: &str) -> Result<Vec<ProxyMetadata>> {
+        Self::parse_with_report(body, &mut ParseReport::new())
+    }
 
+    /// `parse`, noting in `report` how many rows it saw and why any were dropped.
+    pub fn parse_with_report(body: &str, report: &mut ParseReport) -> Result<Vec<ProxyMetadata>> {
         let mut proxies = Vec::new();
-        for line in body.lines() {
-            let line = line.trim();
-            if !line.is_empty() && line.contains(':') {
-                proxies.push(ProxyMetadata {
-                    addr: line.to_string(),
-                    kind: ProxyType::Http,
-                    country: "Unknown".to_string(), // ProxyScrape simple API doesn't return country in this format
-                });
+        for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            report.rows += 1;
+            match line.parse::<ProxyAddr>() {
+                // ProxyScrape simple API doesn't return country in this format
+                Ok(addr) => proxies.push(ProxyMetadata::new(addr, ProxyType::Http, Country::UNKNOWN)),
+                Err(_) => report.reject("invalid address"),
             }
         }
 
-        self.base.update_cache(proxies.clone());
         Ok(proxies)
     }
 }
 
+impl Default for ProxyScrape {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl Provider for ProxyScrape {
     async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
-        self.load_internal().await
+        self.base.load(&self.url, Self::parse_with_report).await
     }
 
     fn name(&self) -> &'static str {
         "api.proxyscrape.com"
     }
 
-    fn set_proxy(&mut self, proxy: String) {
-        self.base.proxy_upstream = proxy;
+    fn set_proxy(&mut self, proxy: Proxy) {
+        self.base.proxy_upstream = Some(proxy);
+    }
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        self.base.report.take()
+    }
+
+    fn set_client_mode(&mut self, mode: ClientMode) {
+        self.base.client_mode = mode;
     }
 }
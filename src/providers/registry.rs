@@ -0,0 +1,174 @@
+use super::cool_proxy::CoolProxy;
+use super::cybersyndrome::Cybersyndrome;
+use super::file::FileProvider;
+use super::free_proxy_list::{FreeProxyList, FreeProxyListSite};
+use super::proxyscrape::ProxyScrape;
+use super::scrape::{ScrapeConfig, ScrapeProvider};
+use super::url_list::UrlListProvider;
+use crate::configuration::{CybersyndromeConfig, FileConfig, ProviderConfig, Settings, UrlListConfig};
+use crate::country::Country;
+use crate::provider::Provider;
+use anyhow::Result;
+use log::warn;
+use std::collections::HashMap;
+
+/// Builds one provider from its `[providers.<name>]` section.
+pub type ProviderFactory = Box<dyn Fn(toml::Value) -> Result<Box<dyn Provider>> + Send + Sync>;
+
+/// Maps `config.toml` section names to provider factories.
+///
+/// A section may be a table or an array of tables (one provider each); entries
+/// with `enabled = false` are skipped before the factory is called.
+pub struct ProviderRegistry {
+    factories: HashMap<String, ProviderFactory>,
+}
+
+impl ProviderRegistry {
+    /// Registry with no providers at all.
+    pub fn empty() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Registry knowing every built-in provider.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register("cool_proxy", |section| {
+            let conf: ProviderConfig = section.try_into()?;
+            Ok(Box::new(CoolProxy::new().with_options(conf.options()?)))
+        });
+        registry.register("free_proxy_list", |section| {
+            let conf: ProviderConfig = section.try_into()?;
+            Ok(Box::new(FreeProxyList::new().with_options(conf.options()?)))
+        });
+        for (name, site) in [
+            ("sslproxies", FreeProxyListSite::SslProxies),
+            ("us_proxy", FreeProxyListSite::UsProxy),
+            ("socks_proxy", FreeProxyListSite::SocksProxy),
+        ] {
+            registry.register(name, move |section| {
+                let conf: ProviderConfig = section.try_into()?;
+                Ok(Box::new(FreeProxyList::for_site(site).with_options(conf.options()?)))
+            });
+        }
+        registry.register("cybersyndrome", |section| {
+            let conf: CybersyndromeConfig = section.try_into()?;
+            let mut provider = Cybersyndrome::new();
+            if !conf.lists.is_empty() {
+                provider = provider.with_lists(&conf.lists);
+            }
+            Ok(Box::new(provider.with_options(conf.options()?)))
+        });
+        registry.register("proxyscrape", |section| {
+            let conf: ProviderConfig = section.try_into()?;
+            Ok(Box::new(ProxyScrape::new().with_options(conf.options()?)))
+        });
+        registry.register("file", |section| {
+            let conf: FileConfig = section.try_into()?;
+            let mut provider = FileProvider::new(&conf.path)
+                .with_format(conf.format)
+                .with_columns(conf.columns)
+                .with_fields(conf.fields);
+            if let Some(kind) = conf.kind {
+                provider = provider.with_default_kind(kind);
+            }
+            Ok(Box::new(provider))
+        });
+        registry.register("url_list", |section| {
+            let conf: UrlListConfig = section.try_into()?;
+            let options = conf.options()?;
+            let mut provider = UrlListProvider::new(&conf.url)
+                .with_format(conf.format)
+                .with_columns(conf.columns)
+                .with_fields(conf.fields);
+            if let Some(name) = &conf.name {
+                provider = provider.with_name(name);
+            }
+            if let Some(kind) = conf.kind {
+                provider = provider.with_default_kind(kind);
+            }
+            if let Some(country) = &conf.country {
+                provider = provider.with_country(Country::parse(country));
+            }
+            Ok(Box::new(provider.with_options(options)))
+        });
+        registry.register("scrape", |section| {
+            let conf: ScrapeConfig = section.try_into()?;
+            Ok(Box::new(ScrapeProvider::new(conf)?))
+        });
+        registry
+    }
+
+    /// Add or replace the factory for `[providers.<name>]` sections.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(toml::Value) -> Result<Box<dyn Provider>> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+
+    pub fn unregister(&mut self, name: &str) {
+        self.factories.remove(name);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Build the providers `section` enables.
+    pub fn create(&self, name: &str, section: toml::Value) -> Result<Vec<Box<dyn Provider>>> {
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("no provider registered as {}", name))?;
+
+        let entries = match section {
+            toml::Value::Array(entries) => entries,
+            single => vec![single],
+        };
+        entries
+            .into_iter()
+            .filter(|entry| entry.get("enabled").and_then(toml::Value::as_bool) != Some(false))
+            .map(factory)
+            .collect()
+    }
+
+    /// Every provider enabled in `settings`. Entries that fail to build and
+    /// sections with no factory are skipped with a warning.
+    pub fn build(&self, settings: &Settings) -> Vec<Box<dyn Provider>> {
+        let sections = match settings.providers.sections() {
+            Ok(sections) => sections,
+            Err(e) => {
+                warn!("cannot read provider settings: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut providers = Vec::new();
+        for (name, section) in sections {
+            if !self.contains(&name) {
+                warn!("no provider registered for [providers.{}]", name);
+                continue;
+            }
+            // One by one, so a broken entry doesn't take its siblings down
+            let entries = match section {
+                toml::Value::Array(entries) => entries,
+                single => vec![single],
+            };
+            for entry in entries {
+                match self.create(&name, entry) {
+                    Ok(built) => providers.extend(built),
+                    Err(e) => warn!("skipping [providers.{}] entry: {}", name, e),
+                }
+            }
+        }
+        providers
+    }
+}
+
+impl Default for ProviderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,297 @@
+use super::base::{BaseProvider, ProviderOptions};
+use super::fixtures::ClientMode;
+use super::health::ParseReport;
+use crate::country::Country;
+use crate::provider::Provider;
+use crate::proxy::{Proxy, ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::debug;
+use regex::Regex;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A list site described in `config.toml` under `[[providers.scrape]]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrapeConfig {
+    pub enabled: bool,
+    pub name: String,
+    pub url: String, // May contain `{page}`, see `Pagination`
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub rows: String, // CSS selector matching one element per proxy
+    #[serde(default = "default_cells")]
+    pub cells: String, // CSS selector for the columns `FieldSpec::column` indexes
+    pub host: FieldSpec, // Either the host alone or a whole `ip:port` / URL
+    pub port: Option<FieldSpec>,
+    pub kind: Option<FieldSpec>,
+    pub country: Option<FieldSpec>,
+    pub default_kind: Option<ProxyType>, // When there's no `kind` field or it doesn't parse
+    pub pagination: Option<Pagination>,
+    pub ttl_secs: Option<u64>,
+    pub proxy: Option<String>, // Fixed upstream proxy URL for fetching the list
+}
+
+fn default_cells() -> String {
+    "td".to_string()
+}
+
+/// Where a value lives in a row and how to clean it up.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldSpec {
+    pub column: Option<usize>,     // Index among the row's `cells`
+    pub selector: Option<String>,  // CSS selector, inside the column if one is given
+    pub attr: Option<String>,      // Read this attribute instead of the text
+    pub regex: Option<String>,     // Keep the first capture group (or the whole match)
+    pub map: HashMap<String, String>, // Replace whole values, matched case-insensitively
+}
+
+/// Either fill `{page}` in the URL from `start` on, or follow the `next` link.
+/// Stops after `max_pages`, at the first page without proxies, or at a page
+/// that fails to load, keeping the pages before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pagination {
+    #[serde(default = "default_start")]
+    pub start: usize,
+    pub next: Option<String>, // CSS selector of the next-page link
+    pub max_pages: usize,
+}
+
+fn default_start() -> usize {
+    1
+}
+
+struct Field {
+    column: Option<usize>,
+    selector: Option<Selector>,
+    attr: Option<String>,
+    regex: Option<Regex>,
+    map: HashMap<String, String>,
+}
+
+impl Field {
+    fn compile(spec: &FieldSpec) -> Result<Self> {
+        Ok(Self {
+            column: spec.column,
+            selector: spec.selector.as_deref().map(parse_selector).transpose()?,
+            attr: spec.attr.clone(),
+            regex: spec.regex.as_deref().map(Regex::new).transpose()?,
+            map: spec
+                .map
+                .iter()
+                .map(|(k, v)| (k.to_lowercase(), v.clone()))
+                .collect(),
+        })
+    }
+
+    fn extract(&self, row: ElementRef, cells: &[ElementRef]) -> Option<String> {
+        let scope = match self.column {
+            Some(idx) => *cells.get(idx)?,
+            None => row,
+        };
+        let element = match &self.selector {
+            Some(selector) => scope.select(selector).next()?,
+            None => scope,
+        };
+        let raw = match &self.attr {
+            Some(attr) => element.value().attr(attr)?.to_string(),
+            None => element.text().collect::<String>(),
+        };
+
+        let mut value = raw.trim().to_string();
+        if let Some(re) = &self.regex {
+            let caps = re.captures(&value)?;
+            value = caps.get(1).or(caps.get(0))?.as_str().trim().to_string();
+        }
+        if let Some(mapped) = self.map.get(&value.to_lowercase()) {
+            value = mapped.clone();
+        }
+        (!value.is_empty()).then_some(value)
+    }
+}
+
+/// Provider driven entirely by a `ScrapeConfig`, for list sites that need no code.
+pub struct ScrapeProvider {
+    base: BaseProvider,
+    config: ScrapeConfig,
+    rows: Selector,
+    cells: Selector,
+    next: Option<Selector>,
+    host: Field,
+    port: Option<Field>,
+    kind: Option<Field>,
+    country: Option<Field>,
+}
+
+impl ScrapeProvider {
+    /// Compiles every selector and regex up front, so mistakes in the config
+    /// surface here rather than on each fetch.
+    pub fn new(config: ScrapeConfig) -> Result<Self> {
+        let mut base = BaseProvider::new();
+        base.headers = config.headers.clone();
+        if let Some(secs) = config.ttl_secs {
+            base.ttl = Duration::from_secs(secs);
+        }
+        if let Some(proxy) = &config.proxy {
+            base.apply(&ProviderOptions::new().with_upstream(proxy)?);
+        }
+        let next = config.pagination.as_ref().and_then(|p| p.next.as_deref());
+
+        Ok(Self {
+            base,
+            rows: parse_selector(&config.rows)?,
+            cells: parse_selector(&config.cells)?,
+            next: next.map(parse_selector).transpose()?,
+            host: Field::compile(&config.host)?,
+            port: config.port.as_ref().map(Field::compile).transpose()?,
+            kind: config.kind.as_ref().map(Field::compile).transpose()?,
+            country: config.country.as_ref().map(Field::compile).transpose()?,
+            config,
+        })
+    }
+
+    async fn load_internal(&mut self) -> Result<Vec<ProxyMetadata>> {
+        if !self.base.should_update() {
+            return Ok(self.base.cached_list());
+        }
+
+        let (start, max_pages) = match &self.config.pagination {
+            Some(p) => (p.start, p.max_pages.max(1)),
+            None => (0, 1),
+        };
+
+        // A failed later page ends pagination but keeps what was parsed so far
+        let mut result = Vec::new();
+        let mut report = ParseReport::new();
+        let mut url = self.config.url.replace("{page}", &start.to_string());
+        let mut first_error = None;
+        for page in 0..max_pages {
+            let body = match self.base.fetch(&url).await {
+                Ok(body) => body,
+                Err(e) if page == 0 => {
+                    first_error = Some(e);
+                    break;
+                }
+                Err(e) => {
+                    report.warn(format!("{}: {}", url, e));
+                    break;
+                }
+            };
+
+            let doc = Html::parse_document(&body);
+            let found = self.parse_page(&doc, &mut report);
+            debug!("{} page {} has {} proxies", self.config.name, url, found.len());
+            if found.is_empty() {
+                break;
+            }
+            result.extend(found);
+
+            url = if self.config.url.contains("{page}") {
+                self.config.url.replace("{page}", &(start + page + 1).to_string())
+            } else if let Some(next) = &self.next {
+                let Some(href) = doc.select(next).next().and_then(|a| a.value().attr("href")) else {
+                    break;
+                };
+                match Url::parse(&url).and_then(|base| base.join(href)) {
+                    Ok(next) => next.to_string(),
+                    Err(e) => {
+                        report.warn(format!("next page link {:?} on {}: {}", href, url, e));
+                        break;
+                    }
+                }
+            } else {
+                break;
+            };
+        }
+
+        let result = match first_error {
+            Some(e) => Err(e),
+            None if result.is_empty() => Err(anyhow!("proxies not found")),
+            None => Ok(result),
+        };
+        let proxies = self.base.check(result, report)?;
+        self.base.update_cache(proxies.clone());
+        Ok(proxies)
+    }
+
+    /// Proxies in one downloaded page.
+    pub fn parse(&self, body: &str) -> Vec<ProxyMetadata> {
+        self.parse_page(&Html::parse_document(body), &mut ParseReport::new())
+    }
+
+    fn parse_page(&self, doc: &Html, report: &mut ParseReport) -> Vec<ProxyMetadata> {
+        let mut result = Vec::new();
+        for row in doc.select(&self.rows) {
+            report.rows += 1;
+            let cells: Vec<_> = row.select(&self.cells).collect();
+            let Some(host) = self.host.extract(row, &cells) else {
+                report.reject("no host");
+                continue;
+            };
+
+            let parsed = match &self.port {
+                Some(port) => match port.extract(row, &cells) {
+                    Some(port) => ProxyAddr::from_host_port(&host, &port).map(|addr| (None, addr)),
+                    None => {
+                        report.reject("no port");
+                        continue;
+                    }
+                },
+                None => ProxyAddr::parse_url(&host),
+            };
+            let Ok((scheme_kind, addr)) = parsed else {
+                report.reject("invalid address");
+                continue;
+            };
+
+            let kind = self
+                .kind
+                .as_ref()
+                .and_then(|k| k.extract(row, &cells))
+                .and_then(|k| k.parse().ok())
+                .or(scheme_kind)
+                .or(self.config.default_kind.clone())
+                .unwrap_or(ProxyType::Http);
+            let country = self
+                .country
+                .as_ref()
+                .and_then(|c| c.extract(row, &cells))
+                .map(|c| Country::parse(&c))
+                .unwrap_or_default();
+            result.push(ProxyMetadata::new(addr, kind, country));
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Provider for ScrapeProvider {
+    async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
+        self.load_internal().await
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn set_proxy(&mut self, proxy: Proxy) {
+        self.base.proxy_upstream = Some(proxy);
+    }
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        self.base.report.take()
+    }
+
+    fn set_client_mode(&mut self, mode: ClientMode) {
+        self.base.client_mode = mode;
+    }
+}
+
+fn parse_selector(s: &str) -> Result<Selector> {
+    Selector::parse(s).map_err(|e| anyhow!("invalid selector {:?}: {}", s, e))
+}
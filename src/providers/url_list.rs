@@ -0,0 +1,128 @@
+use super::base::{BaseProvider, ProviderOptions};
+use super::fixtures::ClientMode;
+use super::health::ParseReport;
+use super::list::{CsvColumns, JsonFields, ListFormat, ListParser};
+use crate::country::Country;
+use crate::provider::Provider;
+use crate::proxy::{Proxy, ProxyMetadata, ProxyType};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Url;
+
+/// Downloads a plain, CSV or JSON proxy list from a URL, such as a raw GitHub
+/// file or an API endpoint.
+pub struct UrlListProvider {
+    base: BaseProvider,
+    name: String,
+    url: String,
+    parser: ListParser,
+}
+
+impl UrlListProvider {
+    /// Named after the URL's host until `with_name` says otherwise.
+    pub fn new(url: &str) -> Self {
+        let name = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| url.to_string());
+        Self {
+            base: BaseProvider::new(),
+            name,
+            url: url.to_string(),
+            parser: ListParser::default(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_format(mut self, format: ListFormat) -> Self {
+        self.parser.format = format;
+        self
+    }
+
+    pub fn with_columns(mut self, columns: CsvColumns) -> Self {
+        self.parser.columns = columns;
+        self
+    }
+
+    pub fn with_fields(mut self, fields: JsonFields) -> Self {
+        self.parser.fields = fields;
+        self
+    }
+
+    pub fn with_default_kind(mut self, kind: ProxyType) -> Self {
+        self.parser.default_kind = kind;
+        self
+    }
+
+    /// Headers, TTL and upstream for fetching the list; a `url` replaces the one
+    /// given to `new`.
+    pub fn with_options(mut self, options: ProviderOptions) -> Self {
+        if let Some(url) = &options.url {
+            self.url = url.clone();
+        }
+        self.base.apply(&options);
+        self
+    }
+
+    /// Country for entries the list doesn't give one for.
+    pub fn with_country(mut self, country: Country) -> Self {
+        self.parser.default_country = country;
+        self
+    }
+
+    /// Proxies in a downloaded list; the URL's extension helps detect the format.
+    pub fn parse(&self, body: &str) -> Result<Vec<ProxyMetadata>> {
+        parse_list(&self.parser, extension(&self.url).as_deref(), body, &mut ParseReport::new())
+    }
+}
+
+// Of the last path segment only, so `/v1.2/proxies` has none
+fn extension(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let segment = url.path_segments()?.next_back()?;
+    segment.rsplit_once('.').map(|(_, ext)| ext.to_string())
+}
+
+fn parse_list(
+    parser: &ListParser,
+    extension: Option<&str>,
+    body: &str,
+    report: &mut ParseReport,
+) -> Result<Vec<ProxyMetadata>> {
+    let proxies = parser.parse_with_report(body, extension, report)?;
+    if proxies.is_empty() {
+        return Err(anyhow!("proxies not found"));
+    }
+    Ok(proxies)
+}
+
+#[async_trait]
+impl Provider for UrlListProvider {
+    async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
+        let parser = &self.parser;
+        let extension = extension(&self.url);
+        self.base
+            .load(&self.url, |body, report| parse_list(parser, extension.as_deref(), body, report))
+            .await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_proxy(&mut self, proxy: Proxy) {
+        self.base.proxy_upstream = Some(proxy);
+    }
+
+    fn take_report(&mut self) -> Option<ParseReport> {
+        self.base.report.take()
+    }
+
+    fn set_client_mode(&mut self, mode: ClientMode) {
+        self.base.client_mode = mode;
+    }
+}
@@ -1,41 +1,198 @@
+use crate::asn::AsnInfo;
+use crate::country::Country;
+use crate::proxy_addr::ProxyAddr;
+use anyhow::anyhow;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
-use std::time::Duration;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The `a`/`h` SOCKS variants have the proxy resolve target hostnames; the plain
+/// ones resolve them locally and send an IP.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ProxyType {
     Http,
     Https,
     Socks4,
+    Socks4a,
     Socks5,
+    Socks5h,
     Unknown,
 }
 
+impl ProxyType {
+    /// URL scheme used to talk to a proxy of this type. HTTPS proxies
+    /// (CONNECT-capable) are still spoken to over plain HTTP.
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            ProxyType::Http | ProxyType::Https | ProxyType::Unknown => "http",
+            ProxyType::Socks4 => "socks4",
+            ProxyType::Socks4a => "socks4a",
+            ProxyType::Socks5 => "socks5",
+            ProxyType::Socks5h => "socks5h",
+        }
+    }
+
+    pub fn is_socks4(&self) -> bool {
+        matches!(self, ProxyType::Socks4 | ProxyType::Socks4a)
+    }
+
+    pub fn is_socks5(&self) -> bool {
+        matches!(self, ProxyType::Socks5 | ProxyType::Socks5h)
+    }
+}
+
+/// Case-insensitive; also accepts URL schemes such as `socks5h://`.
+impl FromStr for ProxyType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let name = s.trim();
+        let name = name.strip_suffix("://").unwrap_or(name);
+        match name.to_ascii_lowercase().as_str() {
+            "http" => Ok(ProxyType::Http),
+            "https" => Ok(ProxyType::Https),
+            "socks4" => Ok(ProxyType::Socks4),
+            "socks4a" => Ok(ProxyType::Socks4a),
+            "socks5" => Ok(ProxyType::Socks5),
+            "socks5h" => Ok(ProxyType::Socks5h),
+            "unknown" => Ok(ProxyType::Unknown),
+            _ => Err(anyhow!("unknown proxy type: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ProxyType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl fmt::Display for ProxyType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ProxyType::Http => write!(f, "HTTP"),
             ProxyType::Https => write!(f, "HTTPS"),
             ProxyType::Socks4 => write!(f, "SOCKS4"),
+            ProxyType::Socks4a => write!(f, "SOCKS4A"),
             ProxyType::Socks5 => write!(f, "SOCKS5"),
+            ProxyType::Socks5h => write!(f, "SOCKS5H"),
             ProxyType::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// How much of the client a proxy reveals, as its list reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Anonymity {
+    Transparent, // Forwards the client's IP
+    Anonymous,   // Hides the IP but admits being a proxy
+    Elite,       // Adds no proxy headers at all
+}
+
+impl fmt::Display for Anonymity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Anonymity::Transparent => write!(f, "transparent"),
+            Anonymity::Anonymous => write!(f, "anonymous"),
+            Anonymity::Elite => write!(f, "elite"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyMetadata {
-    pub addr: String,
+    pub addr: ProxyAddr,
     pub kind: ProxyType,
-    pub country: String,
+    pub country: Country,
+    #[serde(default)]
+    pub geoip_country: Option<Country>, // Country per the GeoIP database, if consulted
+    #[serde(default)]
+    pub asn: Option<AsnInfo>,
+    #[serde(default)]
+    pub anonymity: Option<Anonymity>,
+    #[serde(default)]
+    pub hostname: Option<String>, // Reverse DNS name the list shows
+    #[serde(default)]
+    pub rank: Option<usize>, // Position on a list sorted by quality, 1 = best
+    #[serde(default)]
+    pub google: Option<bool>, // Whether the list says it can reach Google
+    #[serde(default)]
+    pub last_checked: Option<SystemTime>, // When the list last saw it working
+}
+
+impl ProxyMetadata {
+    pub fn new(addr: ProxyAddr, kind: ProxyType, country: Country) -> Self {
+        Self {
+            addr,
+            kind,
+            country,
+            geoip_country: None,
+            asn: None,
+            anonymity: None,
+            hostname: None,
+            rank: None,
+            google: None,
+            last_checked: None,
+        }
+    }
+
+    /// IP of `addr`, if it is a literal address.
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.addr.ip()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Proxy {
-    pub addr: String,
+    pub addr: ProxyAddr,
     pub kind: ProxyType,
-    pub country: String,
-    pub provider: String,
+    pub country: Country,
+    pub providers: Vec<String>, // Every provider that listed this proxy
+    #[serde(rename = "latency_ms", with = "millis")]
     pub latency: Duration,
+    pub uptime: f64, // Share of successful verifications seen for this address and type
+    #[serde(default)]
+    pub exit_ip: Option<IpAddr>, // Egress address observed during verification
+    #[serde(default)]
+    pub geoip_country: Option<Country>, // May disagree with the provider's `country`
+    #[serde(default)]
+    pub asn: Option<AsnInfo>,
+}
+
+impl Proxy {
+    /// Proxy with no providers, measurements or lookups yet; the generator fills
+    /// those in after verification.
+    pub fn new(addr: ProxyAddr, kind: ProxyType, country: Country) -> Self {
+        Self {
+            addr,
+            kind,
+            country,
+            providers: Vec::new(),
+            latency: Duration::ZERO,
+            uptime: 0.0,
+            exit_ip: None,
+            geoip_country: None,
+            asn: None,
+        }
+    }
+
+    /// Number of provider lists vouching for this proxy.
+    pub fn sources(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// `scheme://[user:pass@]host:port` URL for this proxy, password included.
+    pub fn url(&self) -> String {
+        self.addr.url(&self.kind)
+    }
+
+    pub fn to_reqwest(&self) -> anyhow::Result<reqwest::Proxy> {
+        self.addr.to_reqwest(&self.kind)
+    }
 }
 
 impl fmt::Display for Proxy {
@@ -43,7 +200,24 @@ impl fmt::Display for Proxy {
         write!(
             f,
             "{} ({}, {}) - {:?} via {}",
-            self.addr, self.kind, self.country, self.latency, self.provider
+            self.addr,
+            self.kind,
+            self.country,
+            self.latency,
+            self.providers.join(", ")
         )
     }
 }
+
+mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(d.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
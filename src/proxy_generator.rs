@@ -1,11 +1,26 @@
+use crate::asn::AsnDb;
+use crate::configuration::Settings;
+use crate::country::Country;
+use crate::dedupe::Deduplicator;
+use crate::diversity::{DiversityPolicy, DiversityTracker, ExitIpSet};
 use crate::filter::ProxyFilter;
+#[cfg(feature = "geoip")]
+use crate::geoip::GeoIp;
+use crate::priority::{PriorityBuffer, PriorityConfig};
 use crate::provider::Provider;
-use crate::proxy::{Proxy, ProxyMetadata};
+use crate::providers::fixtures::ClientMode;
+use crate::providers::health::{DriftEvent, DriftPolicy, ParseReport};
+use crate::providers::registry::ProviderRegistry;
+use crate::proxy::{Proxy, ProxyMetadata, ProxyType};
+use crate::proxy_addr::ProxyAddr;
+use crate::sampling::{AddrState, SamplingPolicy};
+use crate::verification::{Verification, VerifyOptions};
 
-use log::{error, info};
+use log::{debug, error, info, warn};
 use moka::future::Cache;
-use rand::seq::SliceRandom;
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::Mutex;
 use std::time::Duration;
@@ -16,15 +31,31 @@ pub type VerifyFn = fn(&str) -> BoxFuture<'static, bool>;
 
 use tokio::sync::Semaphore;
 
+// After this long an exit IP may be handed out again
+const EXIT_REUSE_TTL: Duration = Duration::from_secs(20 * 60);
+
 pub struct ProxyGenerator {
-    cache: Cache<String, Option<Duration>>, // Cache stores latency if valid
+    cache: Cache<CheckKey, Option<Verification>>, // Cache stores latency and exit if valid
+    stats: Cache<CheckKey, CheckStats>, // Verification history, for uptime
+    dedupe: Deduplicator, // Merges duplicate entries across providers
     filter: Arc<Mutex<ProxyFilter>>,
+    verify_options: Arc<Mutex<VerifyOptions>>,
+    sampling: Arc<Mutex<SamplingPolicy>>,
+    preferred_countries: Arc<Mutex<Vec<Country>>>, // Unmet quotas of a running batch
     providers: Vec<Arc<Mutex<dyn Provider>>>,
+    #[cfg(feature = "geoip")]
+    geoip: Option<Arc<GeoIp>>, // Fills in missing countries before filtering
+    asn_db: Option<Arc<AsnDb>>, // Attaches ASN and network class before filtering
     proxy_tx: Sender<Proxy>,
     proxy_rx: Arc<Mutex<Receiver<Proxy>>>,
+    priority: Arc<Mutex<Option<PriorityBuffer>>>, // Serve best-scored first instead of FIFO
+    handed_out_exits: Arc<Mutex<Option<ExitIpSet>>>, // Set when deduping by exit IP
     job_tx: Sender<(ProxyMetadata, String)>, // Metadata and Provider Name
     last_valid_proxy: Arc<Mutex<Option<Proxy>>>,
     semaphore: Arc<Semaphore>, // Limit concurrent verifications
+    health: Arc<Mutex<HashMap<String, ParseReport>>>, // Last parse report per provider
+    drift_policy: Arc<Mutex<DriftPolicy>>,
+    drift_tx: broadcast::Sender<DriftEvent>,
 }
 
 impl ProxyGenerator {
@@ -36,39 +67,74 @@ impl ProxyGenerator {
             cache: Cache::builder()
                 .time_to_live(Duration::from_secs(20 * 60))
                 .build(),
+            stats: Cache::builder()
+                .time_to_live(Duration::from_secs(24 * 60 * 60))
+                .build(),
+            dedupe: Deduplicator::new(Duration::from_secs(20 * 60)),
             filter: Arc::new(Mutex::new(ProxyFilter::default())),
+            verify_options: Arc::new(Mutex::new(VerifyOptions::default())),
+            sampling: Arc::new(Mutex::new(SamplingPolicy::default())),
+            preferred_countries: Arc::new(Mutex::new(Vec::new())),
             providers: Vec::new(),
+            #[cfg(feature = "geoip")]
+            geoip: None,
+            asn_db: None,
             proxy_tx,
             proxy_rx: Arc::new(Mutex::new(proxy_rx)),
+            priority: Arc::new(Mutex::new(None)),
+            handed_out_exits: Arc::new(Mutex::new(None)),
             job_tx,
             last_valid_proxy: Arc::new(Mutex::new(None)),
             semaphore: Arc::new(Semaphore::new(200)), // Max 200 concurrent verifications
+            health: Arc::new(Mutex::new(HashMap::new())),
+            drift_policy: Arc::new(Mutex::new(DriftPolicy::default())),
+            drift_tx: broadcast::channel(16).0,
         };
 
         // Spawn workers
         let cache_clone = generator.cache.clone();
+        let stats_clone = generator.stats.clone();
+        let dedupe_clone = generator.dedupe.clone();
         let proxy_tx_clone = generator.proxy_tx.clone();
         let filter_clone = generator.filter.clone();
+        let verify_options_clone = generator.verify_options.clone();
         let semaphore_clone = generator.semaphore.clone();
         
         tokio::spawn(async move {
             while let Some((metadata, provider_name)) = job_rx.recv().await {
                 let cache = cache_clone.clone();
+                let stats = stats_clone.clone();
+                let dedupe = dedupe_clone.clone();
                 let tx = proxy_tx_clone.clone();
                 let filter = filter_clone.clone();
+                let verify_options = verify_options_clone.clone();
                 let permit = semaphore_clone.clone().acquire_owned().await.unwrap();
 
                 tokio::spawn(async move {
                     // Drop permit when this future completes
                     let _permit = permit;
                     
-                    if let Some(latency) = verify_with_cache(cache, &metadata.addr).await {
+                    let options = verify_options.lock().await.clone();
+                    if let Some(verification) = verify_with_cache(cache, stats.clone(), &metadata.addr, &metadata.kind, &options).await {
+                         // Merging may have picked another provider's type; report the one verified
+                         let kind = metadata.kind.clone();
+                         // Other providers may have listed the same address meanwhile
+                         let (metadata, providers) = match dedupe.get(&metadata.addr).await {
+                             Some(merged) => (merged.meta, merged.providers),
+                             None => (metadata, vec![provider_name]),
+                         };
+                         let key = (metadata.addr.clone(), kind.clone());
+                         let uptime = stats.get(&key).await.unwrap_or_default().uptime();
                          let proxy = Proxy {
                              addr: metadata.addr,
-                             kind: metadata.kind,
+                             kind,
                              country: metadata.country,
-                             provider: provider_name,
-                             latency,
+                             providers,
+                             latency: verification.latency,
+                             uptime,
+                             exit_ip: verification.exit_ip,
+                             geoip_country: metadata.geoip_country,
+                             asn: metadata.asn,
                          };
                          
                          // Post-verification filter (e.g. Latency)
@@ -89,34 +155,160 @@ impl ProxyGenerator {
         *f = filter;
     }
 
+    pub async fn set_sampling(&self, policy: SamplingPolicy) {
+        let mut s = self.sampling.lock().await;
+        *s = policy;
+    }
+
+    /// Options for new verifications; results already cached are kept.
+    pub async fn set_verify_options(&self, options: VerifyOptions) {
+        let mut o = self.verify_options.lock().await;
+        *o = options;
+    }
+
+    /// When enabled, `get` never hands out two proxies sharing an exit IP within
+    /// 20 minutes, so rotating through them really changes the visible address.
+    pub async fn set_dedupe_exit_ip(&self, enabled: bool) {
+        let mut exits = self.handed_out_exits.lock().await;
+        *exits = enabled.then(|| ExitIpSet::new(EXIT_REUSE_TTL));
+    }
+
+    /// Enable (`Some`) or disable (`None`) priority delivery: `get` then returns
+    /// the best-scored proxy available rather than the first one verified.
+    /// A capacity of 0 could hold nothing, so it disables priority too.
+    pub async fn set_priority(&self, config: Option<PriorityConfig>) {
+        let config = config.filter(|c| {
+            if c.capacity == 0 {
+                warn!("Priority capacity 0 would drop every proxy; priority delivery disabled");
+            }
+            c.capacity > 0
+        });
+        let mut p = self.priority.lock().await;
+        *p = config.map(PriorityBuffer::new);
+    }
+
+    /// Thresholds for parser drift events.
+    pub async fn set_drift_policy(&self, policy: DriftPolicy) {
+        let mut p = self.drift_policy.lock().await;
+        *p = policy;
+    }
+
+    /// Record or replay every provider's pages from now on.
+    pub async fn set_client_mode(&self, mode: ClientMode) {
+        for provider in &self.providers {
+            provider.lock().await.set_client_mode(mode.clone());
+        }
+    }
+
+    /// Receive a `DriftEvent` whenever a provider's fresh parse looks broken:
+    /// it failed, its yield collapsed, or most of its rows were rejected.
+    pub fn subscribe_drift(&self) -> broadcast::Receiver<DriftEvent> {
+        self.drift_tx.subscribe()
+    }
+
+    /// Report of each provider's latest fresh parse, keyed by provider name.
+    pub async fn parser_health(&self) -> HashMap<String, ParseReport> {
+        self.health.lock().await.clone()
+    }
+
     pub fn add_provider<P: Provider + 'static>(&mut self, provider: P) {
         self.providers.push(Arc::new(Mutex::new(provider)));
     }
 
+    /// Generator with every provider enabled in `settings`.
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self::from_settings_with(settings, &ProviderRegistry::new())
+    }
+
+    /// Like `from_settings`, resolving sections through `registry` so
+    /// third-party providers can be configured too.
+    pub fn from_settings_with(settings: &Settings, registry: &ProviderRegistry) -> Self {
+        let mut generator = Self::new();
+        for provider in registry.build(settings) {
+            info!("using provider {}", provider.name());
+            generator.add_provider(provider);
+        }
+        generator
+    }
+
+    /// Look up the ASN of every listed address before the pre-verification filter.
+    pub fn set_asn_db(&mut self, asn_db: AsnDb) {
+        self.asn_db = Some(Arc::new(asn_db));
+    }
+
+    /// Look up every listed address in `geoip` before the pre-verification filter.
+    #[cfg(feature = "geoip")]
+    pub fn set_geoip(&mut self, geoip: GeoIp) {
+        self.geoip = Some(Arc::new(geoip));
+    }
+
     pub async fn run(&self) {
         let providers = self.providers.clone();
         let job_tx = self.job_tx.clone();
+        let dedupe = self.dedupe.clone();
         let last_valid_proxy = self.last_valid_proxy.clone();
         let filter_mutex = self.filter.clone();
+        let sampling_mutex = self.sampling.clone();
+        let preferred_countries = self.preferred_countries.clone();
+        let cache = self.cache.clone();
+        #[cfg(feature = "geoip")]
+        let geoip = self.geoip.clone();
+        let asn_db = self.asn_db.clone();
+        let health = self.health.clone();
+        let drift_policy = self.drift_policy.clone();
+        let drift_tx = self.drift_tx.clone();
 
         for provider in providers {
             let provider = provider.clone();
             let job_tx = job_tx.clone();
+            let dedupe = dedupe.clone();
             let last_valid_proxy = last_valid_proxy.clone();
             let filter_mutex = filter_mutex.clone();
+            let sampling_mutex = sampling_mutex.clone();
+            let preferred_countries = preferred_countries.clone();
+            let cache = cache.clone();
+            #[cfg(feature = "geoip")]
+            let geoip = geoip.clone();
+            let asn_db = asn_db.clone();
+            let health = health.clone();
+            let drift_policy = drift_policy.clone();
+            let drift_tx = drift_tx.clone();
 
             tokio::spawn(async move {
                 loop {
                     let mut provider_guard = provider.lock().await; // Lock individual provider
                     
-                    // Set upstream proxy if available
+                    // Set upstream proxy if available and usable by the HTTP client (not SOCKS4)
                     let last_valid = last_valid_proxy.lock().await.clone();
-                    if let Some(valid_proxy) = last_valid {
-                         provider_guard.set_proxy(valid_proxy.addr);
+                    if let Some(valid_proxy) = last_valid.filter(|p| p.to_reqwest().is_ok()) {
+                         provider_guard.set_proxy(valid_proxy);
+                    }
+
+                    let listed = provider_guard.list().await;
+                    if let Some(report) = provider_guard.take_report() {
+                        let policy = drift_policy.lock().await.clone();
+                        record_health(provider_guard.name(), report, &policy, &health, &drift_tx).await;
                     }
 
-                    match provider_guard.list().await {
+                    match listed {
                         Ok(mut proxies) => {
+                            #[cfg(feature = "geoip")]
+                            if let Some(geoip) = &geoip {
+                                for meta in proxies.iter_mut() {
+                                    geoip.enrich(meta);
+                                }
+                            }
+                            if let Some(asn_db) = &asn_db {
+                                for meta in proxies.iter_mut() {
+                                    asn_db.enrich(meta);
+                                }
+                            }
+
+                            // Record provenance for the whole list, not just the sampled part
+                            for meta in &proxies {
+                                dedupe.record(provider_guard.name(), meta).await;
+                            }
+
                             // Pre-verification filter (Type, Country)
                             {
                                 let filter = filter_mutex.lock().await;
@@ -124,15 +316,21 @@ impl ProxyGenerator {
                             }
                         
                             info!("{} found ips {}", provider_guard.name(), proxies.len());
-                            {
-                                let mut rng = rand::thread_rng();
-                                proxies.shuffle(&mut rng);
-                            }
-                            
-                            // Limit to 10 proxies to avoid overwhelming
-                            proxies.truncate(10);
-                            
+
+                            // Pick unseen entries first, walking the list across rounds
+                            let states = address_states(&cache, &dedupe, &proxies).await;
+                            let proxies = {
+                                let sampling = sampling_mutex.lock().await;
+                                let preferred = preferred_countries.lock().await;
+                                sampling.sample(proxies, &states, provider_guard.ranked(), &preferred)
+                            };
+                            debug!("{} sampled ips {}", provider_guard.name(), proxies.len());
+
                             for proxy in proxies {
+                                // Skip addresses another provider already dispatched
+                                if !dedupe.claim(&proxy.addr).await {
+                                    continue;
+                                }
                                 if let Err(e) = job_tx.send((proxy, provider_guard.name().to_string())).await {
                                      error!("failed to send job: {}", e);
                                 }
@@ -155,27 +353,173 @@ impl ProxyGenerator {
     }
 
     pub async fn get(&self) -> Option<Proxy> {
+        loop {
+            let proxy = self.next_verified().await?;
+
+            let exits = self.handed_out_exits.lock().await.clone();
+            if let (Some(exits), Some(exit_ip)) = (exits, proxy.exit_ip) {
+                if !exits.first_use(exit_ip).await {
+                    debug!("skipping {}: exit {} already handed out", proxy.addr, exit_ip);
+                    continue;
+                }
+            }
+
+            let mut last_valid = self.last_valid_proxy.lock().await;
+            *last_valid = Some(proxy.clone());
+            return Some(proxy);
+        }
+    }
+
+    async fn next_verified(&self) -> Option<Proxy> {
         let mut rx = self.proxy_rx.lock().await;
-        if let Some(proxy) = rx.recv().await {
-             let mut last_valid = self.last_valid_proxy.lock().await;
-             *last_valid = Some(proxy.clone());
-             return Some(proxy);
+        let mut priority = self.priority.lock().await;
+
+        match priority.as_mut() {
+            Some(buffer) => {
+                // Rank everything verified so far, waiting only if nothing is
+                while let Ok(proxy) = rx.try_recv() {
+                    buffer.push(proxy);
+                }
+                if buffer.is_empty() {
+                    buffer.push(rx.recv().await?);
+                }
+                buffer.pop()
+            }
+            None => rx.recv().await,
+        }
+    }
+
+    /// Collect up to `count` proxies spread according to `policy`, giving up after
+    /// `timeout`. Proxies that don't fit the policy are discarded.
+    pub async fn get_batch(
+        &self,
+        count: usize,
+        policy: &DiversityPolicy,
+        timeout: Duration,
+    ) -> Vec<Proxy> {
+        let mut tracker = DiversityTracker::new(policy, count);
+        let mut batch = Vec::new();
+
+        let search = async {
+            while !tracker.is_full() {
+                // Steer provider sampling towards countries still below their minimum
+                *self.preferred_countries.lock().await = tracker.wanted_countries();
+
+                match self.get().await {
+                    Some(proxy) => {
+                        if tracker.accept(&proxy) {
+                            batch.push(proxy);
+                        }
+                    }
+                    None => break,
+                }
+            }
+        };
+        let _ = tokio::time::timeout(timeout, search).await;
+
+        self.preferred_countries.lock().await.clear();
+        batch
+    }
+}
+
+async fn address_states(
+    cache: &Cache<CheckKey, Option<Verification>>,
+    dedupe: &Deduplicator,
+    proxies: &[ProxyMetadata],
+) -> Vec<AddrState> {
+    let mut states = Vec::with_capacity(proxies.len());
+    for meta in proxies {
+        // A recent dispatch wins, so good entries aren't re-sent every round
+        let state = if dedupe.is_claimed(&meta.addr).await {
+            AddrState::Pending
+        } else {
+            match cache.get(&(meta.addr.clone(), meta.kind.clone())).await {
+                Some(Some(_)) => AddrState::Good,
+                Some(None) => AddrState::Bad,
+                None => AddrState::Unseen,
+            }
+        };
+        states.push(state);
+    }
+    states
+}
+
+async fn record_health(
+    provider: &str,
+    report: ParseReport,
+    policy: &DriftPolicy,
+    health: &Mutex<HashMap<String, ParseReport>>,
+    drift_tx: &broadcast::Sender<DriftEvent>,
+) {
+    debug!("{} parse: {}", provider, report);
+    for warning in &report.warnings {
+        warn!("{} parser: {}", provider, warning);
+    }
+    if let Some(reason) = report.drift(policy) {
+        warn!("{} parser drift: {}", provider, reason);
+        // No subscribers is fine
+        let _ = drift_tx.send(DriftEvent {
+            provider: provider.to_string(),
+            reason,
+            report: report.clone(),
+        });
+    }
+    health.lock().await.insert(provider.to_string(), report);
+}
+
+impl Default for ProxyGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Each type is checked differently, so results are kept per address and type
+type CheckKey = (ProxyAddr, ProxyType);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CheckStats {
+    checks: u32,
+    successes: u32,
+}
+
+impl CheckStats {
+    fn uptime(&self) -> f64 {
+        if self.checks == 0 {
+            return 0.0;
         }
-        None
+        self.successes as f64 / self.checks as f64
     }
 }
 
-async fn verify_with_cache(cache: Cache<String, Option<Duration>>, proxy: &str) -> Option<Duration> {
-    if let Some(val) = cache.get(proxy).await {
+async fn verify_with_cache(
+    cache: Cache<CheckKey, Option<Verification>>,
+    stats: Cache<CheckKey, CheckStats>,
+    proxy: &ProxyAddr,
+    kind: &ProxyType,
+    options: &VerifyOptions,
+) -> Option<Verification> {
+    let key = (proxy.clone(), kind.clone());
+    if let Some(val) = cache.get(&key).await {
         return val;
     }
     
     // Use the verification logic from verification mod
-    let res = crate::verification::verify_proxy(proxy).await;
+    let res = crate::verification::verify_proxy(proxy, kind, options).await;
     // Cache the result (Some(duration) or None). 
     // If None (invalid), we might not want to cache it forever, but for now we do to avoid retrying bad proxies immediately.
     // Actually if it's bad, maybe we don't cache correct latency, but 'None' implies bad.
-    // However, the cache type is Option<Duration>.
-    cache.insert(proxy.to_string(), res).await;
+    // However, the cache type is Option<Verification>.
+    cache.insert(key.clone(), res).await;
+    stats
+        .entry(key)
+        .and_upsert_with(|existing| {
+            let mut s = existing.map(|e| e.into_value()).unwrap_or_default();
+            s.checks += 1;
+            if res.is_some() {
+                s.successes += 1;
+            }
+            std::future::ready(s)
+        })
+        .await;
     res
 }
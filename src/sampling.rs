@@ -0,0 +1,98 @@
+use crate::country::Country;
+use crate::proxy::ProxyMetadata;
+use rand::seq::SliceRandom;
+
+/// What we already know about an address when picking a round's sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrState {
+    Unseen,  // Not verified lately, or the result expired
+    Pending, // Dispatched recently; verification may still be running
+    Good,    // Fresh positive cache entry
+    Bad,     // Fresh negative cache entry
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleOrder {
+    /// Provider order for providers that rank their list, random otherwise.
+    Auto,
+    ProviderOrder,
+    Random,
+}
+
+/// Decides which entries of a provider's list get verified in a round.
+///
+/// Unseen addresses are picked first, so over successive rounds the sampler walks
+/// through the whole list instead of re-drawing known entries. Good ones fill
+/// the rest of the batch to be served again; fresh failures and pending
+/// verifications are skipped.
+#[derive(Debug, Clone)]
+pub struct SamplingPolicy {
+    pub batch_size: usize,
+    pub order: SampleOrder,
+}
+
+impl SamplingPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn with_order(mut self, order: SampleOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// `states[i]` describes `proxies[i]`; `ranked` tells whether the provider
+    /// returns its best entries first. Entries from `preferred` countries are
+    /// moved to the front, e.g. to fill diversity quotas.
+    pub fn sample(
+        &self,
+        proxies: Vec<ProxyMetadata>,
+        states: &[AddrState],
+        ranked: bool,
+        preferred: &[Country],
+    ) -> Vec<ProxyMetadata> {
+        let mut unseen = Vec::new();
+        let mut good = Vec::new();
+        for (meta, state) in proxies.into_iter().zip(states) {
+            match state {
+                AddrState::Unseen => unseen.push(meta),
+                AddrState::Good => good.push(meta),
+                AddrState::Pending | AddrState::Bad => {}
+            }
+        }
+
+        let keep_order = match self.order {
+            SampleOrder::Auto => ranked,
+            SampleOrder::ProviderOrder => true,
+            SampleOrder::Random => false,
+        };
+        if !keep_order {
+            let mut rng = rand::thread_rng();
+            unseen.shuffle(&mut rng);
+            good.shuffle(&mut rng);
+        }
+        let mut picked = unseen;
+        picked.extend(good);
+        if !preferred.is_empty() {
+            // Stable, so the order chosen above holds within each group
+            picked.sort_by_key(|meta| !preferred.contains(&meta.country));
+        }
+
+        picked.truncate(self.batch_size);
+        picked
+    }
+}
+
+impl Default for SamplingPolicy {
+    fn default() -> Self {
+        Self {
+            batch_size: 10,
+            order: SampleOrder::Auto,
+        }
+    }
+}
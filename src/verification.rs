@@ -1,7 +1,13 @@
+use anyhow::{anyhow, Result};
 use log::{debug, error};
 use reqwest::Client;
 use serde::Deserialize;
-use std::net::{IpAddr, ToSocketAddrs};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_socks::tcp::Socks4Stream;
+use tokio_socks::TargetAddr;
+use crate::proxy::ProxyType;
+use crate::proxy_addr::{Host, ProxyAddr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::time::Duration;
 
 #[derive(Deserialize)]
@@ -9,6 +15,20 @@ struct CheckIP {
     origin: String,
 }
 
+/// Outcome of a successful verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Verification {
+    pub latency: Duration,
+    pub exit_ip: Option<IpAddr>, // Address the judge saw the request coming from
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    /// Accept proxies whose exit address differs from the listed one
+    /// (e.g. load-balanced or chained proxies).
+    pub accept_foreign_exit: bool,
+}
+
 fn is_safe_ip(ip: IpAddr) -> bool {
     // Check for loopback and other unsafe ranges
     if ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() {
@@ -16,45 +36,132 @@ fn is_safe_ip(ip: IpAddr) -> bool {
     }
 
     match ip {
-        IpAddr::V4(ipv4) => {
-            let octets = ipv4.octets();
-            // private networks
-            // 10.0.0.0/8
-            if octets[0] == 10 { return false; }
-            // 172.16.0.0/12
-            if octets[0] == 172 && (16..=31).contains(&octets[1]) { return false; }
-            // 192.168.0.0/16
-            if octets[0] == 192 && octets[1] == 168 { return false; }
-            // link-local 169.254.0.0/16
-            if octets[0] == 169 && octets[1] == 254 { return false; }
-            // broadcast
-            if ipv4.is_broadcast() { return false; }
-            
-            true
-        },
-        IpAddr::V6(ipv6) => {
-            // unique local (fc00::/7)
-            if (ipv6.segments()[0] & 0xfe00) == 0xfc00 { return false; }
-            // link-local (fe80::/10)
-            if (ipv6.segments()[0] & 0xffc0) == 0xfe80 { return false; }
-            
-            true
-        }
+        IpAddr::V4(ipv4) => is_safe_ipv4(ipv4),
+        IpAddr::V6(ipv6) => is_safe_ipv6(ipv6),
+    }
+}
+
+fn is_safe_ipv4(ipv4: Ipv4Addr) -> bool {
+    if ipv4.is_loopback() || ipv4.is_unspecified() || ipv4.is_multicast() {
+        return false;
     }
+
+    let octets = ipv4.octets();
+    // private networks
+    // 10.0.0.0/8
+    if octets[0] == 10 { return false; }
+    // 172.16.0.0/12
+    if octets[0] == 172 && (16..=31).contains(&octets[1]) { return false; }
+    // 192.168.0.0/16
+    if octets[0] == 192 && octets[1] == 168 { return false; }
+    // link-local 169.254.0.0/16
+    if octets[0] == 169 && octets[1] == 254 { return false; }
+    // broadcast
+    if ipv4.is_broadcast() { return false; }
+    // "this network" 0.0.0.0/8
+    if octets[0] == 0 { return false; }
+    // shared address space (CGNAT) 100.64.0.0/10
+    if octets[0] == 100 && (64..=127).contains(&octets[1]) { return false; }
+
+    true
 }
 
-pub async fn verify_proxy(proxy: &str) -> Option<Duration> {
-    // strict parsing
-    let addr = match proxy.to_socket_addrs() {
-        Ok(mut addrs) => match addrs.next() {
-            Some(a) => a,
-            None => {
-                error!("cannot resolve proxy address {}", proxy);
-                return None;
-            }
-        },
+fn is_safe_ipv6(ipv6: Ipv6Addr) -> bool {
+    let segments = ipv6.segments();
+
+    // IPv4-mapped (::ffff:0:0/96) and deprecated IPv4-compatible (::/96) addresses
+    // reach the embedded IPv4 address
+    if let Some(ipv4) = ipv6.to_ipv4() {
+        return is_safe_ipv4(ipv4);
+    }
+    // NAT64 well-known prefix (64:ff9b::/96)
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        return is_safe_ipv4(Ipv4Addr::from(((segments[6] as u32) << 16) | segments[7] as u32));
+    }
+    // 6to4 (2002::/16) embeds an IPv4 address in the next 32 bits
+    if segments[0] == 0x2002 {
+        return is_safe_ipv4(Ipv4Addr::from(((segments[1] as u32) << 16) | segments[2] as u32));
+    }
+    // unique local (fc00::/7)
+    if (segments[0] & 0xfe00) == 0xfc00 { return false; }
+    // link-local (fe80::/10)
+    if (segments[0] & 0xffc0) == 0xfe80 { return false; }
+    // deprecated site-local (fec0::/10)
+    if (segments[0] & 0xffc0) == 0xfec0 { return false; }
+    // documentation (2001:db8::/32)
+    if segments[0] == 0x2001 && segments[1] == 0xdb8 { return false; }
+    // discard-only (100::/64)
+    if segments[..4] == [0x100, 0, 0, 0] { return false; }
+
+    true
+}
+
+const JUDGE_HOST: &str = "httpbin.org";
+const JUDGE_PATH: &str = "/ip";
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Use http instead of https to support HTTP-only proxies. SOCKS5 resolves the
+// judge locally, SOCKS5h leaves it to the proxy.
+async fn check_http_client(proxy: &ProxyAddr, kind: &ProxyType) -> Result<CheckIP> {
+    let client = Client::builder()
+        .proxy(proxy.to_reqwest(kind)?)
+        .timeout(VERIFY_TIMEOUT)
+        .pool_max_idle_per_host(0) // Disable pooling for one-off requests
+        .build()?;
+
+    let resp = client
+        .get(format!("http://{}{}", JUDGE_HOST, JUDGE_PATH))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(resp.json().await?)
+}
+
+// SOCKS4 only carries an IPv4 target, resolved here; SOCKS4a sends the judge's
+// hostname for the proxy to resolve. The username doubles as the SOCKS4 user ID.
+async fn check_socks4(proxy: &ProxyAddr, kind: &ProxyType) -> Result<CheckIP> {
+    let proxy_addr = proxy.resolve()?;
+    let target = if *kind == ProxyType::Socks4a {
+        TargetAddr::Domain(JUDGE_HOST.into(), 80)
+    } else {
+        let judge = tokio::net::lookup_host((JUDGE_HOST, 80))
+            .await?
+            .find(|a| a.is_ipv4())
+            .ok_or_else(|| anyhow!("{} has no IPv4 address", JUDGE_HOST))?;
+        TargetAddr::Ip(judge)
+    };
+    let userid = proxy.credentials.as_ref().map(|c| c.username.as_str());
+
+    let exchange = async {
+        let mut stream = match userid {
+            Some(userid) => Socks4Stream::connect_with_userid(proxy_addr, target, userid).await?,
+            None => Socks4Stream::connect(proxy_addr, target).await?,
+        };
+        // HTTP/1.0 so the body is neither chunked nor kept alive
+        let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", JUDGE_PATH, JUDGE_HOST);
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await?;
+        Ok::<_, anyhow::Error>(response)
+    };
+    let response = tokio::time::timeout(VERIFY_TIMEOUT, exchange).await??;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow!("truncated HTTP response"))?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(anyhow!("judge returned status {}", status));
+    }
+    Ok(serde_json::from_str(body)?)
+}
+
+pub async fn verify_proxy(proxy: &ProxyAddr, kind: &ProxyType, options: &VerifyOptions) -> Option<Verification> {
+    let addr = match proxy.resolve() {
+        Ok(a) => a,
         Err(e) => {
-            error!("cannot parse proxy address {}: {}", proxy, e);
+            error!("cannot resolve proxy address {}: {}", proxy, e);
             return None;
         }
     };
@@ -64,54 +171,124 @@ pub async fn verify_proxy(proxy: &str) -> Option<Duration> {
         return None;
     }
 
-    let proxy_url = match reqwest::Proxy::http(&format!("http://{}", proxy)) {
-        Ok(url) => url,
-        Err(e) => {
-            error!("cannot parse proxy {}: {}", proxy, e);
-            return None;
-        }
-    };
-
-    let client = match Client::builder()
-        .proxy(proxy_url)
-        .timeout(Duration::from_secs(10))
-        .pool_max_idle_per_host(0) // Disable pooling for one-off requests
-        .build()
-    {
-        Ok(client) => client,
-        Err(e) => {
-            error!("cannot build client for verify {}: {}", proxy, e);
-            return None;
-        }
+    // Connect to the address we just checked, not whatever the host resolves to next
+    let checked = ProxyAddr {
+        host: Host::Ip(addr.ip()),
+        port: addr.port(),
+        credentials: proxy.credentials.clone(),
     };
 
     let start = std::time::Instant::now();
-    // Use http instead of https to support HTTP-only proxies
-    let resp = match client.get("http://httpbin.org/ip").send().await {
-        Ok(resp) => resp,
+    // reqwest has no SOCKS4, so those are checked over a hand-made connection
+    let result = if kind.is_socks4() {
+        check_socks4(&checked, kind).await
+    } else {
+        check_http_client(&checked, kind).await
+    };
+    let check = match result {
+        Ok(check) => check,
         Err(e) => {
             debug!("cannot verify proxy {}: {}", proxy, e);
             return None;
         }
     };
 
-    if !resp.status().is_success() {
-        return None;
+    let latency = start.elapsed();
+
+    // httpbin returns "IP1, IP2" if multiple, or just "IP".
+    // The last hop is the address that actually connected to the judge.
+    let exit_ip = check
+        .origin
+        .split(',')
+        .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+        .next_back();
+
+    let entry_ip = addr.ip().to_canonical();
+    let exits_at_entry = check
+        .origin
+        .split(',')
+        .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+        .any(|ip| ip.to_canonical() == entry_ip);
+
+    if exits_at_entry || (options.accept_foreign_exit && exit_ip.is_some()) {
+        return Some(Verification { latency, exit_ip });
     }
+    debug!("proxy {} exits through foreign address {}", proxy, check.origin);
+    None
+}
 
-    let check: CheckIP = match resp.json().await {
-        Ok(c) => c,
-        Err(e) => {
-            error!("cannot unmarshal checkIP for {}: {}", proxy, e);
-            return None;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safe(ip: &str) -> bool {
+        is_safe_ip(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn public_ipv6_is_safe() {
+        assert!(safe("2606:4700::1111"));
+        assert!(safe("2a00:1450:4001::200e"));
+    }
+
+    #[test]
+    fn special_ipv6_ranges_are_unsafe() {
+        for ip in [
+            "::1",          // loopback
+            "::",           // unspecified
+            "ff02::1",      // multicast
+            "fc00::1",      // unique local
+            "fd12:3456::1", // unique local
+            "fe80::1",      // link-local
+            "febf::1",      // link-local, end of range
+            "fec0::1",      // site-local
+            "feff::1",      // site-local, end of range
+            "2001:db8::1",  // documentation
+            "100::1",       // discard-only
+        ] {
+            assert!(!safe(ip), "{}", ip);
         }
-    };
+        // Just outside those ranges
+        assert!(safe("fe00::1"));
+        assert!(safe("2001:db9::1"));
+        assert!(safe("100:0:0:1::1"));
+    }
 
-    // httpbin returns "IP1, IP2" if multiple, or just "IP".
-    // We just check if our proxy IP is contained in the origin string.
-    // The previous logic was checking prefix.
-    if check.origin.contains(proxy.split(':').next().unwrap_or("")) {
-        return Some(start.elapsed());
+    #[test]
+    fn embedded_ipv4_is_checked() {
+        // IPv4-mapped and IPv4-compatible
+        assert!(!safe("::ffff:127.0.0.1"));
+        assert!(!safe("::ffff:10.1.2.3"));
+        assert!(!safe("::192.168.1.1"));
+        assert!(safe("::ffff:8.8.8.8"));
+        // NAT64
+        assert!(!safe("64:ff9b::7f00:1"));
+        assert!(!safe("64:ff9b::a9fe:a9fe"));
+        assert!(safe("64:ff9b::808:808"));
+        // 6to4
+        assert!(!safe("2002:c0a8:0101::1"));
+        assert!(!safe("2002:6440:0001::1"));
+        assert!(safe("2002:0808:0808::1"));
+    }
+
+    #[test]
+    fn private_ipv4_is_unsafe() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "172.31.255.255",
+            "192.168.0.1",
+            "169.254.1.1",
+            "100.64.0.1",
+            "0.1.2.3",
+            "255.255.255.255",
+            "224.0.0.1",
+        ] {
+            assert!(!safe(ip), "{}", ip);
+        }
+        for ip in ["8.8.8.8", "172.32.0.1", "100.128.0.1", "192.169.0.1"] {
+            assert!(safe(ip), "{}", ip);
+        }
     }
-    None
 }
@@ -0,0 +1,56 @@
+use proxyrs::asn::{AsnDb, NetworkClass};
+use proxyrs::country::Country;
+use proxyrs::filter::ProxyFilter;
+use proxyrs::proxy::{ProxyMetadata, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+
+fn db() -> AsnDb {
+    AsnDb::open(format!("{}/tests/fixtures/asn.tsv", env!("CARGO_MANIFEST_DIR"))).unwrap()
+}
+
+fn meta(host: &str) -> ProxyMetadata {
+    ProxyMetadata::new(ProxyAddr::new(host.parse().unwrap(), 8080), ProxyType::Http, Country::UNKNOWN)
+}
+
+fn lookup(db: &AsnDb, ip: &str) -> Option<(u32, NetworkClass)> {
+    db.lookup(ip.parse().unwrap()).map(|info| (info.number, info.class))
+}
+
+#[test]
+fn looks_up_ranges_and_classifies() {
+    let db = db();
+    assert_eq!(lookup(&db, "51.15.1.2"), Some((12876, NetworkClass::Hosting)));
+    assert_eq!(lookup(&db, "77.0.12.1"), Some((3320, NetworkClass::Residential)));
+    assert_eq!(lookup(&db, "100.0.0.9"), Some((22394, NetworkClass::Mobile)));
+    assert_eq!(lookup(&db, "2001:470::1"), Some((6939, NetworkClass::Residential)));
+    // Between ranges, and in a "Not routed" one
+    assert_eq!(lookup(&db, "1.0.1.0"), None);
+    assert_eq!(lookup(&db, "5.0.0.1"), None);
+}
+
+#[test]
+fn overrides_beat_the_keyword_guess() {
+    let db = db().with_class(13335, NetworkClass::Hosting);
+    assert_eq!(lookup(&db, "1.0.0.1"), Some((13335, NetworkClass::Hosting)));
+}
+
+#[test]
+fn enriched_metadata_feeds_the_filter() {
+    let db = db();
+    let mut hosted = meta("51.15.1.2");
+    let mut home = meta("77.0.12.1");
+    let mut named = ProxyMetadata::new("proxy.example.com:8080".parse().unwrap(), ProxyType::Http, Country::UNKNOWN);
+    for meta in [&mut hosted, &mut home, &mut named] {
+        db.enrich(meta);
+    }
+    assert_eq!(hosted.asn.as_ref().map(|a| a.org.as_str()), Some("Online S.A.S."));
+    assert!(named.asn.is_none());
+
+    let filter = ProxyFilter::new().with_exclude_network_classes(vec![NetworkClass::Hosting]);
+    assert!(!filter.filter_metadata(&hosted));
+    assert!(filter.filter_metadata(&home));
+
+    let filter = ProxyFilter::new().with_include_asns(vec![3320]);
+    assert!(filter.filter_metadata(&home));
+    assert!(!filter.filter_metadata(&named));
+}
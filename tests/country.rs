@@ -0,0 +1,69 @@
+use proxyrs::country::{Country, CountrySelector, Region};
+
+fn code(s: &str) -> Option<&'static str> {
+    Country::parse(s).code()
+}
+
+#[test]
+fn parses_codes_and_names() {
+    assert_eq!(code("de"), Some("DE"));
+    assert_eq!(code(" DEU "), Some("DE"));
+    assert_eq!(code("Germany"), Some("DE"));
+    assert_eq!(code("UK"), Some("GB"));
+    assert_eq!(code("Korea, Republic of"), Some("KR"));
+    assert_eq!(code("Côte d'Ivoire"), Some("CI"));
+    assert_eq!(code("The Bahamas"), Some("BS"));
+    assert_eq!(code("Bosnia & Herzegovina"), Some("BA"));
+    assert_eq!(code("Viet Nam"), Some("VN"));
+
+    let country = Country::parse("usa");
+    assert_eq!((country.alpha3(), country.name()), (Some("USA"), Some("United States")));
+}
+
+#[test]
+fn unknown_values_stay_unknown() {
+    for s in ["", "-", "N/A", "Unknown", "XX", "Atlantis"] {
+        assert_eq!(Country::parse(s), Country::UNKNOWN, "{:?}", s);
+    }
+    assert!("N/A".parse::<Country>().is_err());
+    assert_eq!(Country::UNKNOWN.to_string(), "Unknown");
+    assert_eq!(Country::UNKNOWN.name(), None);
+}
+
+#[test]
+fn serializes_as_code_or_null() {
+    assert_eq!(serde_json::to_string(&Country::parse("France")).unwrap(), r#""FR""#);
+    assert_eq!(serde_json::to_string(&Country::UNKNOWN).unwrap(), "null");
+    assert_eq!(serde_json::from_str::<Country>(r#""Japan""#).unwrap(), Country::parse("JP"));
+    assert_eq!(serde_json::from_str::<Country>("null").unwrap(), Country::UNKNOWN);
+}
+
+#[test]
+fn regions_contain_their_members() {
+    let de = Country::parse("DE");
+    assert!(Region::EuropeanUnion.contains(de));
+    assert!(Region::Europe.contains(de), "EU members are in Europe too");
+    assert!(Region::Europe.contains(Country::parse("NO")));
+    assert!(!Region::EuropeanUnion.contains(Country::parse("NO")));
+    assert!(Region::LatinAmerica.contains(Country::parse("MX")));
+    assert!(!Region::Asia.contains(Country::UNKNOWN));
+}
+
+#[test]
+fn selectors_prefer_regions_but_keep_namibia() {
+    let parse = |s: &str| s.parse::<CountrySelector>().unwrap();
+    assert_eq!(parse("EU"), CountrySelector::Region(Region::EuropeanUnion));
+    assert_eq!(parse("Latin America"), CountrySelector::Region(Region::LatinAmerica));
+    assert_eq!(parse("Middle-East"), CountrySelector::Region(Region::MiddleEast));
+    // "NA" is Namibia's code, not North America
+    assert_eq!(parse("NA"), CountrySelector::Country(Country::parse("Namibia")));
+    assert!(parse("NA").matches(Country::parse("NAM")));
+    assert!(!parse("NA").matches(Country::parse("US")));
+    assert!("Narnia".parse::<CountrySelector>().is_err());
+}
+
+#[test]
+fn unknown_country_matches_no_selector() {
+    assert!(!CountrySelector::Country(Country::UNKNOWN).matches(Country::UNKNOWN));
+    assert!(!CountrySelector::Region(Region::Africa).matches(Country::UNKNOWN));
+}
@@ -0,0 +1,60 @@
+use proxyrs::asn::{AsnInfo, NetworkClass};
+use proxyrs::country::Country;
+use proxyrs::dedupe::Deduplicator;
+use proxyrs::proxy::{Anonymity, ProxyMetadata, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+use std::time::{Duration, SystemTime};
+
+fn meta(kind: ProxyType, country: &str) -> ProxyMetadata {
+    ProxyMetadata::new(ProxyAddr::new("1.2.3.4".parse().unwrap(), 1080), kind, Country::parse(country))
+}
+
+#[tokio::test]
+async fn merges_listings_of_one_address() {
+    let dedupe = Deduplicator::new(Duration::from_secs(60));
+    let earlier = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let later = earlier + Duration::from_secs(60);
+
+    let mut first = meta(ProxyType::Http, "");
+    first.last_checked = Some(later);
+    first.rank = Some(3);
+    let mut second = meta(ProxyType::Socks5, "DE");
+    second.last_checked = Some(earlier);
+    second.rank = Some(1);
+    second.anonymity = Some(Anonymity::Elite);
+    second.asn = Some(AsnInfo { number: 64512, org: "Example".into(), class: NetworkClass::Hosting });
+
+    dedupe.record("a", &first).await;
+    dedupe.record("b", &second).await;
+    dedupe.record("a", &first).await;
+
+    let merged = dedupe.get(&first.addr).await.unwrap();
+    assert_eq!(merged.providers, ["a", "b"]);
+    assert_eq!(merged.sources(), 2);
+    // The more specific type and the first known value of each field win
+    assert_eq!(merged.meta.kind, ProxyType::Socks5);
+    assert_eq!(merged.meta.country, Country::parse("DE"));
+    assert_eq!(merged.meta.rank, Some(3));
+    assert_eq!(merged.meta.anonymity, Some(Anonymity::Elite));
+    assert_eq!(merged.meta.asn.map(|a| a.number), Some(64512));
+    assert_eq!(merged.meta.last_checked, Some(later));
+}
+
+#[tokio::test]
+async fn claims_expire_while_address_stays_listed() {
+    let dedupe = Deduplicator::new(Duration::from_secs(60)).with_dispatch_ttl(Duration::from_millis(200));
+    let listing = meta(ProxyType::Http, "US");
+
+    dedupe.record("a", &listing).await;
+    assert!(dedupe.claim(&listing.addr).await);
+    assert!(!dedupe.claim(&listing.addr).await);
+    assert!(dedupe.is_claimed(&listing.addr).await);
+
+    // Re-listing the address does not extend the claim
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        dedupe.record("b", &listing).await;
+    }
+    assert!(!dedupe.is_claimed(&listing.addr).await);
+    assert!(dedupe.claim(&listing.addr).await);
+}
@@ -0,0 +1,69 @@
+use proxyrs::country::Country;
+use proxyrs::diversity::{DiversityPolicy, DiversityTracker, ExitIpSet};
+use proxyrs::proxy::{Proxy, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+use std::time::Duration;
+
+fn proxy(ip: &str, country: &str, providers: &[&str]) -> Proxy {
+    let addr = ProxyAddr::new(ip.parse().unwrap(), 8080);
+    Proxy {
+        providers: providers.iter().map(|p| p.to_string()).collect(),
+        ..Proxy::new(addr, ProxyType::Http, Country::parse(country))
+    }
+}
+
+#[test]
+fn caps_per_country_and_subnet() {
+    let policy = DiversityPolicy::new().with_max_per_country(2).with_max_per_subnet(1);
+    let mut tracker = DiversityTracker::new(&policy, 10);
+
+    assert!(tracker.accept(&proxy("1.1.1.1", "US", &["a"])));
+    assert!(!tracker.accept(&proxy("1.1.1.1", "US", &["b"])), "same address twice");
+    assert!(!tracker.accept(&proxy("1.1.1.2", "US", &["a"])), "same /24");
+    assert!(tracker.accept(&proxy("2.2.2.2", "US", &["a"])));
+    assert!(!tracker.accept(&proxy("3.3.3.3", "US", &["a"])), "third US proxy");
+    assert!(tracker.accept(&proxy("3.3.3.3", "DE", &["a"])));
+}
+
+#[test]
+fn counts_every_listing_provider() {
+    let policy = DiversityPolicy::new().with_max_per_provider(1);
+    let mut tracker = DiversityTracker::new(&policy, 10);
+
+    assert!(tracker.accept(&proxy("1.1.1.1", "US", &["a", "b"])));
+    // `b` already supplied the first proxy, even though it wasn't listed first
+    assert!(!tracker.accept(&proxy("2.2.2.2", "US", &["b"])));
+    assert!(!tracker.accept(&proxy("3.3.3.3", "US", &["c", "a"])));
+    assert!(tracker.accept(&proxy("4.4.4.4", "US", &["c"])));
+}
+
+#[test]
+fn reserves_slots_for_country_minimums() {
+    let policy = DiversityPolicy::new().with_min_per_country(vec!["DE".into(), "Japan".into()], 1);
+    let mut tracker = DiversityTracker::new(&policy, 3);
+
+    let mut wanted = tracker.wanted_countries();
+    wanted.sort_by_key(|c| c.to_string());
+    assert_eq!(wanted, vec![Country::parse("DE"), Country::parse("JP")]);
+
+    assert!(tracker.accept(&proxy("1.1.1.1", "US", &["a"])));
+    // The two remaining slots are held for DE and JP
+    assert!(!tracker.accept(&proxy("2.2.2.2", "US", &["a"])));
+    assert!(tracker.accept(&proxy("3.3.3.3", "JP", &["a"])));
+    assert_eq!(tracker.wanted_countries(), vec![Country::parse("DE")]);
+    assert!(tracker.accept(&proxy("4.4.4.4", "DE", &["a"])));
+    assert!(tracker.is_full());
+}
+
+#[tokio::test]
+async fn exit_ips_are_handed_out_once_per_ttl() {
+    let exits = ExitIpSet::new(Duration::from_millis(200));
+    let (a, b) = ("203.0.113.1".parse().unwrap(), "203.0.113.2".parse().unwrap());
+
+    assert!(exits.first_use(a).await);
+    assert!(exits.first_use(b).await);
+    assert!(!exits.first_use(a).await);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(exits.first_use(a).await);
+}
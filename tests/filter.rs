@@ -0,0 +1,30 @@
+use proxyrs::country::Country;
+use proxyrs::filter::{IpFamily, ProxyFilter};
+use proxyrs::proxy::{ProxyMetadata, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+
+fn meta(host: &str) -> ProxyMetadata {
+    ProxyMetadata::new(ProxyAddr::new(host.parse().unwrap(), 8080), ProxyType::Http, Country::UNKNOWN)
+}
+
+#[test]
+fn ip_family_matches_literal_addresses() {
+    let v4 = meta("1.2.3.4");
+    let v6 = meta("2606:4700::1111");
+
+    let only_v4 = ProxyFilter::new().with_ip_family(IpFamily::V4);
+    assert!(only_v4.filter_metadata(&v4));
+    assert!(!only_v4.filter_metadata(&v6));
+
+    let only_v6 = ProxyFilter::new().with_ip_family(IpFamily::V6);
+    assert!(!only_v6.filter_metadata(&v4));
+    assert!(only_v6.filter_metadata(&v6));
+}
+
+#[test]
+fn ip_family_drops_hostnames() {
+    let host = meta("proxy.example.com");
+    assert!(ProxyFilter::new().filter_metadata(&host));
+    assert!(!ProxyFilter::new().with_ip_family(IpFamily::V4).filter_metadata(&host));
+    assert!(!ProxyFilter::new().with_ip_family(IpFamily::V6).filter_metadata(&host));
+}
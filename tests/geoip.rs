@@ -0,0 +1,44 @@
+#![cfg(feature = "geoip")]
+
+use proxyrs::country::Country;
+use proxyrs::geoip::GeoIp;
+use proxyrs::proxy::{ProxyMetadata, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+
+fn csv_db(name: &str, data: &str) -> GeoIp {
+    let path = std::env::temp_dir().join(format!("proxyrs-{}-{}.csv", name, std::process::id()));
+    std::fs::write(&path, data).unwrap();
+    let db = GeoIp::open_csv(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    db
+}
+
+#[test]
+fn csv_lookup_prefers_most_specific_range() {
+    let db = csv_db(
+        "ranges",
+        "cidr,country\n# comment\n10.0.0.0/8,US\n10.1.2.3/16,DE\n10.1.2.0/24,FR\n2001:db8::/32,JP\n",
+    );
+
+    let lookup = |ip: &str| db.lookup(ip.parse().unwrap()).and_then(|c| c.code());
+    assert_eq!(lookup("10.1.2.200"), Some("FR"));
+    assert_eq!(lookup("10.1.9.1"), Some("DE")); // Host bits in the CSV are ignored
+    assert_eq!(lookup("10.200.0.1"), Some("US"));
+    assert_eq!(lookup("2001:db8::1"), Some("JP"));
+    assert_eq!(lookup("192.0.2.1"), None);
+}
+
+#[test]
+fn enrich_keeps_provider_country() {
+    let db = csv_db("enrich", "192.0.2.0/24,NL\n");
+    let addr = ProxyAddr::new("192.0.2.7".parse().unwrap(), 8080);
+
+    let mut unknown = ProxyMetadata::new(addr.clone(), ProxyType::Http, Country::UNKNOWN);
+    db.enrich(&mut unknown);
+    assert_eq!(unknown.country.code(), Some("NL"));
+
+    let mut listed = ProxyMetadata::new(addr, ProxyType::Http, Country::parse("US"));
+    db.enrich(&mut listed);
+    assert_eq!(listed.country.code(), Some("US"));
+    assert_eq!(listed.geoip_country.and_then(|c| c.code()), Some("NL"));
+}
@@ -0,0 +1,39 @@
+use proxyrs::country::Country;
+use proxyrs::proxy::{ProxyMetadata, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+use proxyrs::providers::health::{DriftPolicy, DriftReason, ParseReport};
+
+// Built directly, since parsing already refuses some of these
+fn meta(host: &str, port: u16) -> ProxyMetadata {
+    ProxyMetadata::new(ProxyAddr::new(host.parse().unwrap(), port), ProxyType::Http, Country::UNKNOWN)
+}
+
+#[test]
+fn validate_rejects_bogus_addresses() {
+    let mut report = ParseReport::new();
+    let valid = report.validate(vec![meta("1.2.3.4", 80), meta("0.0.0.0", 80), meta("1.2.3.5", 0)]);
+
+    assert_eq!(valid.len(), 1);
+    assert_eq!((report.rows, report.accepted), (3, 1));
+    assert_eq!(report.rejected.get("invalid address"), Some(&2));
+    assert!(matches!(report.drift(&DriftPolicy::default()), Some(DriftReason::MostlyRejected { rejected: 2, rows: 3 })));
+}
+
+#[test]
+fn drift_on_collapsed_yield() {
+    let report = ParseReport { rows: 5, accepted: 5, previous: Some(300), ..Default::default() };
+    assert_eq!(
+        report.drift(&DriftPolicy::default()),
+        Some(DriftReason::YieldCollapsed { previous: 300, current: 5 })
+    );
+
+    // Small lists fluctuate too much to judge
+    let report = ParseReport { rows: 1, accepted: 1, previous: Some(6), ..Default::default() };
+    assert_eq!(report.drift(&DriftPolicy::default()), None);
+}
+
+#[test]
+fn drift_on_parse_failure() {
+    let report = ParseReport { error: Some("ip not found".to_string()), ..Default::default() };
+    assert_eq!(report.drift(&DriftPolicy::default()), Some(DriftReason::ParseFailed("ip not found".to_string())));
+}
@@ -0,0 +1,42 @@
+use proxyrs::providers::js_expr::Scope;
+
+fn scope() -> Scope {
+    let mut scope = Scope::new();
+    scope.set_array("ps", vec![Some(80), Some(8080), None, Some(0)]);
+    scope.set("n", 7);
+    scope.set("z", 3);
+    scope
+}
+
+#[test]
+fn evaluates_arithmetic() {
+    let scope = scope();
+    assert_eq!(scope.eval("1+2*3").unwrap(), 7);
+    assert_eq!(scope.eval("(1+2)*3").unwrap(), 9);
+    assert_eq!(scope.eval("10-4-3").unwrap(), 3);
+    assert_eq!(scope.eval("-n+2*-3").unwrap(), -13);
+    assert_eq!(scope.eval("(100+ps[1]*2)%120").unwrap(), 60);
+    assert_eq!(scope.eval("-7%3").unwrap(), -1);
+    assert_eq!(scope.eval("ps[n-6]/4").unwrap(), 2020);
+    assert_eq!(scope.eval("n*0").unwrap(), 0);
+    assert_eq!(scope.eval("ps[0]*0+1").unwrap(), 1);
+    assert_eq!(scope.eval("ps[z]*0").unwrap(), 0);
+    assert_eq!(scope.eval("n*ps[z]").unwrap(), 0);
+}
+
+#[test]
+fn reports_errors() {
+    let scope = scope();
+    let err = |expr: &str| scope.eval(expr).unwrap_err().to_string();
+    assert!(err("zz[0]").contains("unknown array `zz`"));
+    assert!(err("m+1").contains("unknown variable `m`"));
+    assert!(err("ps[4]").contains("out of range"));
+    assert!(err("ps[2]").contains("not an integer"));
+    assert!(err("1/0").contains("division by zero"));
+    assert!(err("7/2").contains("not an integer"));
+    assert!(err("1 2").contains("unexpected `2`"));
+    assert!(err("(1+2").contains("expected `)`"));
+    assert!(err("Math.floor(1)").contains("unsupported character `.`"));
+    assert!(err("9223372036854775807+1").contains("overflow"));
+    assert!(err(&format!("{}1{}", "(".repeat(100), ")".repeat(100))).contains("nested deeper"));
+}
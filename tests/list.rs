@@ -0,0 +1,177 @@
+use proxyrs::country::Country;
+use proxyrs::providers::file::FileProvider;
+use proxyrs::providers::health::ParseReport;
+use proxyrs::providers::list::{CsvColumns, JsonFields, ListFormat, ListParser};
+use proxyrs::provider::Provider;
+use proxyrs::proxy::{ProxyMetadata, ProxyType};
+
+fn entries(proxies: &[ProxyMetadata]) -> Vec<(String, ProxyType, Option<&'static str>)> {
+    proxies.iter().map(|m| (m.addr.to_string(), m.kind.clone(), m.country.code())).collect()
+}
+
+fn entry(addr: &str, kind: ProxyType, country: Option<&'static str>) -> (String, ProxyType, Option<&'static str>) {
+    (addr.to_string(), kind, country)
+}
+
+#[test]
+fn plain_lines_and_urls() {
+    let parser = ListParser { default_kind: ProxyType::Socks5, ..Default::default() };
+    let text = "# exported\n1.2.3.4:1080\n\n  http://user:pw@5.6.7.8:3128  \n[2001:db8::1]:1080\nnot-a-proxy\n";
+    let mut report = ParseReport::new();
+    let proxies = parser.parse_with_report(text, Some("txt"), &mut report).unwrap();
+
+    assert_eq!(
+        entries(&proxies),
+        [
+            entry("1.2.3.4:1080", ProxyType::Socks5, None),
+            entry("user:***@5.6.7.8:3128", ProxyType::Http, None),
+            entry("[2001:db8::1]:1080", ProxyType::Socks5, None),
+        ]
+    );
+    assert_eq!((report.rows, report.rejected_total()), (4, 1));
+}
+
+#[test]
+fn csv_with_default_and_custom_columns() {
+    let text = "Host,Port,Type,Country,Username,Password\n\
+                1.2.3.4,8080,socks4,Germany,,\n\
+                2001:db8::2,1080,socks5,JP,bob,secret\n\
+                5.6.7.8,,http,US,,\n";
+    let mut report = ParseReport::new();
+    let proxies = ListParser::default().parse_with_report(text, Some("CSV"), &mut report).unwrap();
+    assert_eq!(
+        entries(&proxies),
+        [
+            entry("1.2.3.4:8080", ProxyType::Socks4, Some("DE")),
+            entry("bob:***@[2001:db8::2]:1080", ProxyType::Socks5, Some("JP")),
+        ]
+    );
+    assert_eq!(report.rejected.get("missing address"), Some(&1));
+
+    let columns = CsvColumns { address: "proxy".into(), kind: "protocol".into(), ..Default::default() };
+    let parser = ListParser { format: ListFormat::Csv, columns, ..Default::default() };
+    let proxies = parser.parse("proxy,protocol\n9.9.9.9:80,https\n", None).unwrap();
+    assert_eq!(entries(&proxies), [entry("9.9.9.9:80", ProxyType::Https, None)]);
+
+    let err = ListParser::default().parse("ip,country\n1.2.3.4,US\n", Some("csv")).unwrap_err();
+    assert!(err.to_string().contains("neither"), "{}", err);
+}
+
+#[test]
+fn proxychains_section_only() {
+    let text = "strict_chain\nsocks5 127.0.0.1 9050\n\n[ProxyList]\n# tor\nsocks5 1.2.3.4 1080 user pass\nhttp 2001:db8::3 8080 # local\nsocks4 broken\n";
+    let mut report = ParseReport::new();
+    let proxies = ListParser::default().parse_with_report(text, None, &mut report).unwrap();
+    assert_eq!(
+        entries(&proxies),
+        [
+            entry("user:***@1.2.3.4:1080", ProxyType::Socks5, None),
+            entry("[2001:db8::3]:8080", ProxyType::Http, None),
+        ]
+    );
+    assert_eq!(report.rejected.get("missing port"), Some(&1));
+}
+
+#[test]
+fn default_country_fills_gaps_only() {
+    let parser = ListParser { default_country: Country::parse("FR"), ..Default::default() };
+    let text = "host,port,country\n1.2.3.4,80,\n1.2.3.5,80,Spain\n1.2.3.6,80,Nowhere\n";
+    let countries: Vec<_> = parser.parse(text, Some("csv")).unwrap().iter().map(|m| m.country.code()).collect();
+    assert_eq!(countries, [Some("FR"), Some("ES"), Some("FR")]);
+}
+
+#[tokio::test]
+async fn file_provider_reads_by_extension() {
+    let path = std::env::temp_dir().join(format!("proxyrs-list-{}.csv", std::process::id()));
+    std::fs::write(&path, "host,port,type\n1.2.3.4,1080,socks5\n").unwrap();
+    let mut provider = FileProvider::new(&path);
+    let proxies = provider.list().await;
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(entries(&proxies.unwrap()), [entry("1.2.3.4:1080", ProxyType::Socks5, None)]);
+}
+
+#[tokio::test]
+async fn file_provider_reports_each_fresh_read() {
+    let path = std::env::temp_dir().join(format!("proxyrs-report-{}.txt", std::process::id()));
+    std::fs::write(&path, "1.2.3.4:1080
+1.2.3.5:0
+nonsense
+").unwrap();
+    let mut provider = FileProvider::new(&path);
+    let proxies = provider.list().await.unwrap();
+
+    assert_eq!(proxies.len(), 1);
+    let report = provider.take_report().unwrap();
+    assert_eq!((report.rows, report.accepted, report.rejected_total()), (3, 1, 2));
+
+    // An unchanged file is served from the cache without a new report
+    provider.list().await.unwrap();
+    assert!(provider.take_report().is_none());
+
+    // A file that no longer parses keeps the failure for `parser_health`
+    let mut provider = FileProvider::new(&path).with_format(ListFormat::Json);
+    let result = provider.list().await;
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(result.is_err());
+    assert!(provider.take_report().unwrap().error.is_some());
+}
+
+#[test]
+fn json_field_paths() {
+    let text = r#"{"data": {"items": [
+        {"ip": "1.2.3.4", "port": 8080, "protocols": ["socks5", "http"], "geo": {"country": "NL"}},
+        {"ip": "5.6.7.8", "protocols": ["http"]},
+        {"ip": "2001:db8::4", "port": "3128", "protocols": []}
+    ]}}"#;
+    let fields = JsonFields {
+        items: Some("data.items".into()),
+        host: Some("ip".into()),
+        kind: Some("protocols.0".into()),
+        country: Some("geo.country".into()),
+        ..Default::default()
+    };
+    let parser = ListParser { fields, ..Default::default() };
+    let mut report = ParseReport::new();
+    let proxies = parser.parse_with_report(text, Some("json"), &mut report).unwrap();
+
+    assert_eq!(
+        entries(&proxies),
+        [
+            entry("1.2.3.4:8080", ProxyType::Socks5, Some("NL")),
+            entry("[2001:db8::4]:3128", ProxyType::Http, None),
+        ]
+    );
+    assert_eq!((report.rows, report.rejected.get("missing address")), (3, Some(&1)));
+
+    let fields = JsonFields { items: Some("missing".into()), ..Default::default() };
+    let parser = ListParser { fields, ..Default::default() };
+    assert!(parser.parse(text, Some("json")).is_err());
+}
+
+#[test]
+fn json_common_keys_and_url_strings() {
+    let text = r#"[
+        "socks5://9.9.9.9:1080",
+        {"host": "1.2.3.4", "port": 80, "type": "https", "country_code": "br", "user": "u", "pass": "p"},
+        {"url": "http://5.6.7.8:3128"},
+        {"addr": {"host": "::1", "port": 1080}, "kind": "socks5h"},
+        {"addr": {"host": "bad host", "port": 1}},
+        42
+    ]"#;
+    let mut report = ParseReport::new();
+    // Detected from the contents without an extension
+    let proxies = ListParser::default().parse_with_report(text, None, &mut report).unwrap();
+
+    assert_eq!(
+        entries(&proxies),
+        [
+            entry("9.9.9.9:1080", ProxyType::Socks5, None),
+            entry("u:***@1.2.3.4:80", ProxyType::Https, Some("BR")),
+            entry("5.6.7.8:3128", ProxyType::Http, None),
+            entry("[::1]:1080", ProxyType::Socks5h, None),
+        ]
+    );
+    assert_eq!((report.rows, report.rejected_total()), (6, 2));
+}
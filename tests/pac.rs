@@ -0,0 +1,88 @@
+use proxyrs::country::Country;
+use proxyrs::pac::{render_pac, PacConfig};
+use proxyrs::proxy::{Proxy, ProxyType};
+use std::time::Duration;
+
+fn proxy(addr: &str, kind: ProxyType, country: &str, latency_ms: u64) -> Proxy {
+    Proxy {
+        latency: Duration::from_millis(latency_ms),
+        ..Proxy::new(addr.parse().unwrap(), kind, Country::parse(country))
+    }
+}
+
+fn pool() -> Vec<Proxy> {
+    vec![
+        proxy("1.1.1.1:8080", ProxyType::Http, "US", 300),
+        proxy("2.2.2.2:1080", ProxyType::Socks5, "DE", 100),
+        proxy("3.3.3.3:1080", ProxyType::Socks4, "FR", 200),
+        proxy("[2001:db8::1]:3128", ProxyType::Https, "JP", 400),
+    ]
+}
+
+// The `return` of the catch-all route, the script's last statement
+fn default_route(script: &str) -> &str {
+    script.lines().rev().find_map(|l| l.trim().strip_prefix("return ")).unwrap()
+}
+
+#[test]
+fn default_route_fails_over_fastest_first() {
+    let script = render_pac(&pool(), &PacConfig::new());
+    assert!(script.starts_with("function FindProxyForURL(url, host) {\n"));
+    assert!(script.ends_with("}\n"));
+    assert_eq!(
+        default_route(&script),
+        r#""SOCKS5 2.2.2.2:1080; SOCKS 3.3.3.3:1080; PROXY 1.1.1.1:8080; DIRECT";"#
+    );
+
+    let script = render_pac(&pool(), &PacConfig::new().with_failover(1).with_direct_fallback(false));
+    assert_eq!(default_route(&script), r#""SOCKS5 2.2.2.2:1080";"#);
+
+    // 0 would leave routes empty, so it counts as 1
+    let script = render_pac(&pool(), &PacConfig::new().with_failover(0));
+    assert_eq!(default_route(&script), r#""SOCKS5 2.2.2.2:1080; DIRECT";"#);
+}
+
+#[test]
+fn country_and_region_rules() {
+    let config = PacConfig::new()
+        .with_rule("*.example.jp", "Japan")
+        .with_rule(".example.eu", "EU")
+        .with_direct_fallback(false);
+    let script = render_pac(&pool(), &config);
+
+    assert!(script.contains(
+        "    if (host == \"example.jp\" || dnsDomainIs(host, \".example.jp\"))\n        return \"PROXY [2001:db8::1]:3128\";\n"
+    ));
+    assert!(script.contains(
+        "    if (host == \"example.eu\" || dnsDomainIs(host, \".example.eu\"))\n        return \"SOCKS5 2.2.2.2:1080; SOCKS 3.3.3.3:1080\";\n"
+    ));
+    // Rules come before the catch-all, in the order given
+    let jp = script.find("example.jp").unwrap();
+    let eu = script.find("example.eu").unwrap();
+    assert!(jp < eu && eu < script.rfind("return").unwrap());
+}
+
+#[test]
+fn rule_without_proxies_goes_direct() {
+    let config = PacConfig::new().with_rule("example.br", "BR").with_direct_fallback(false);
+    let script = render_pac(&pool(), &config);
+    assert!(script.contains("dnsDomainIs(host, \".example.br\"))\n        return \"DIRECT\";"));
+
+    let script = render_pac(&[], &PacConfig::new().with_direct_fallback(false));
+    assert_eq!(default_route(&script), r#""DIRECT";"#);
+}
+
+#[test]
+fn invalid_rules_are_dropped() {
+    let config = PacConfig::new()
+        .with_rule("example.com", "Atlantis")
+        .with_rule("bad domain!", "US")
+        .with_rule("", "US")
+        .with_rule("10.0.0.1", "US")
+        .with_rule("Example.COM", "US");
+
+    assert_eq!(config.rules.len(), 1);
+    assert_eq!(config.rules[0].domain, "example.com");
+    let script = render_pac(&pool(), &config);
+    assert_eq!(script.matches("dnsDomainIs").count(), 1);
+}
@@ -0,0 +1,51 @@
+use proxyrs::country::Country;
+use proxyrs::priority::{PriorityBuffer, PriorityConfig, ScoreWeights};
+use proxyrs::proxy::{Proxy, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+use std::time::Duration;
+
+fn proxy(port: u16, latency_ms: u64, uptime: f64, providers: &[&str]) -> Proxy {
+    let addr = ProxyAddr::new("1.2.3.4".parse().unwrap(), port);
+    Proxy {
+        providers: providers.iter().map(|p| p.to_string()).collect(),
+        latency: Duration::from_millis(latency_ms),
+        uptime,
+        ..Proxy::new(addr, ProxyType::Http, Country::UNKNOWN)
+    }
+}
+
+fn ports(buffer: &mut PriorityBuffer) -> Vec<u16> {
+    std::iter::from_fn(|| buffer.pop()).map(|p| p.addr.port).collect()
+}
+
+#[test]
+fn pops_best_score_first() {
+    let mut buffer = PriorityBuffer::new(PriorityConfig::new());
+    buffer.push(proxy(1, 2000, 1.0, &["a"]));
+    buffer.push(proxy(2, 100, 1.0, &["a", "b"]));
+    buffer.push(proxy(3, 100, 0.0, &["a"]));
+    buffer.push(proxy(4, 100, 1.0, &["a"]));
+
+    assert_eq!(ports(&mut buffer), vec![2, 4, 3, 1]);
+}
+
+#[test]
+fn weights_change_the_order() {
+    let latency_only = ScoreWeights { latency: 1.0, uptime: 0.0, sources: 0.0 };
+    let mut buffer = PriorityBuffer::new(PriorityConfig::new().with_weights(latency_only));
+    buffer.push(proxy(1, 500, 1.0, &["a", "b", "c"]));
+    buffer.push(proxy(2, 50, 0.0, &["a"]));
+
+    assert_eq!(ports(&mut buffer), vec![2, 1]);
+}
+
+#[test]
+fn drops_lowest_scores_beyond_capacity() {
+    let mut buffer = PriorityBuffer::new(PriorityConfig::new().with_capacity(2));
+    buffer.push(proxy(1, 3000, 1.0, &["a"]));
+    buffer.push(proxy(2, 100, 1.0, &["a"]));
+    buffer.push(proxy(3, 1000, 1.0, &["a"]));
+
+    assert_eq!(buffer.len(), 2);
+    assert_eq!(ports(&mut buffer), vec![2, 3]);
+}
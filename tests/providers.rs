@@ -0,0 +1,297 @@
+// Parsers run against saved pages in `tests/fixtures`, without the network.
+
+use proxyrs::country::Country;
+use proxyrs::proxy::{Anonymity, Proxy, ProxyMetadata, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+use proxyrs::provider::Provider;
+use proxyrs::providers::base::{BaseProvider, ProviderOptions};
+use proxyrs::providers::cool_proxy::CoolProxy;
+use proxyrs::providers::cybersyndrome::{Cybersyndrome, CybersyndromeList};
+use proxyrs::providers::fixtures::{ClientMode, FixtureStore};
+use proxyrs::providers::free_proxy_list::FreeProxyList;
+use proxyrs::providers::health::ParseReport;
+use proxyrs::providers::proxyscrape::ProxyScrape;
+use proxyrs::providers::registry::ProviderRegistry;
+use proxyrs::providers::url_list::UrlListProvider;
+use std::time::Duration;
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn age(proxy: &ProxyMetadata) -> Duration {
+    proxy.last_checked.unwrap().elapsed().unwrap()
+}
+
+fn addrs(proxies: &[ProxyMetadata]) -> Vec<String> {
+    proxies.iter().map(|p| p.addr.host_port()).collect()
+}
+
+#[test]
+fn cool_proxy_decodes_addresses() {
+    let proxies = CoolProxy::parse(&fixture("cool_proxy.html")).unwrap();
+
+    // The ad row in between has neither an address nor a port
+    assert_eq!(
+        addrs(&proxies),
+        ["177.93.33.21:8080", "45.70.236.194:999", "103.152.112.162:80", "51.158.68.133:8811"]
+    );
+    assert!(proxies.iter().all(|p| p.kind == ProxyType::Http && p.country == Country::UNKNOWN));
+}
+
+#[test]
+fn cool_proxy_rejects_page_without_list() {
+    assert!(CoolProxy::parse("<html><body><div id=\"main\"></div></body></html>").is_err());
+}
+
+#[test]
+fn free_proxy_list_reads_table() {
+    let proxies = FreeProxyList::parse(&fixture("free_proxy_list.html")).unwrap();
+
+    assert_eq!(
+        addrs(&proxies),
+        ["20.111.54.16:8123", "43.153.207.93:3128", "181.129.62.2:47377", "8.219.97.248:80", "190.61.88.147:8080"]
+    );
+    let kinds: Vec<_> = proxies.iter().map(|p| p.kind.clone()).collect();
+    assert_eq!(kinds, [ProxyType::Https, ProxyType::Https, ProxyType::Http, ProxyType::Http, ProxyType::Http]);
+    let countries: Vec<_> = proxies.iter().filter_map(|p| p.country.code()).collect();
+    assert_eq!(countries, ["FR", "US", "CO", "SG", "GT"]);
+
+    let anonymity: Vec<_> = proxies.iter().map(|p| p.anonymity).collect();
+    let (a, e) = (Some(Anonymity::Anonymous), Some(Anonymity::Elite));
+    assert_eq!(anonymity, [a, e, e, a, e]);
+    assert!(proxies.iter().all(|p| p.google == Some(false)));
+    assert!(age(&proxies[4]) >= Duration::from_secs(5 * 60));
+}
+
+#[test]
+fn free_proxy_list_ignores_overflowing_age() {
+    let body = fixture("free_proxy_list.html").replace("1 mins ago", "999999999999999999 days ago");
+    let proxies = FreeProxyList::parse(&body).unwrap();
+    assert_eq!(proxies.len(), 5);
+    assert_eq!(proxies[0].last_checked, None);
+    assert!(proxies[1].last_checked.is_some());
+}
+
+#[test]
+fn socks_proxy_reads_version_column() {
+    let proxies = FreeProxyList::parse(&fixture("socks_proxy.html")).unwrap();
+
+    let kinds: Vec<_> = proxies.iter().map(|p| p.kind.clone()).collect();
+    assert_eq!(kinds, [ProxyType::Socks4, ProxyType::Socks5, ProxyType::Socks4, ProxyType::Socks5]);
+    assert_eq!(proxies[3].country.code(), Some("CA"));
+    assert!(proxies.iter().all(|p| p.anonymity == Some(Anonymity::Anonymous) && p.google.is_none()));
+
+    let hour = Duration::from_secs(60 * 60);
+    assert!((hour..2 * hour).contains(&age(&proxies[1])));
+    assert!(age(&proxies[2]) < hour);
+}
+
+#[test]
+fn free_proxy_list_rejects_page_without_list() {
+    assert!(FreeProxyList::parse("<html><body><table></table></body></html>").is_err());
+}
+
+#[test]
+fn proxyscrape_skips_invalid_lines() {
+    let proxies = ProxyScrape::parse(&fixture("proxyscrape.txt")).unwrap();
+
+    assert_eq!(
+        addrs(&proxies),
+        ["8.219.97.248:80", "47.74.152.29:8888", "[2001:db8::10]:3128", "103.49.202.252:80"]
+    );
+    assert!(proxies.iter().all(|p| p.kind == ProxyType::Http));
+}
+
+#[test]
+fn cybersyndrome_reads_ranking() {
+    let proxies = Cybersyndrome::parse(&fixture("cybersyndrome.html")).unwrap();
+
+    assert_eq!(proxies.len(), 30);
+    assert_eq!(
+        addrs(&proxies[..4]),
+        ["203.99.240.182:80", "211.128.96.206:80", "192.73.244.36:80", "34.146.245.42:8080"]
+    );
+    assert_eq!(proxies[7].addr.host_port(), "190.6.54.12:6969");
+    let ports: Vec<_> = proxies.iter().take(10).map(|p| p.addr.port).collect();
+    assert_eq!(ports, [80, 80, 80, 8080, 80, 1080, 7890, 6969, 8080, 3128]);
+    let countries: Vec<_> = proxies.iter().take(6).filter_map(|p| p.country.code()).collect();
+    assert_eq!(countries, ["JP", "JP", "US", "US", "JP", "CN"]);
+
+    let third = &proxies[2];
+    assert_eq!(third.rank, Some(3));
+    assert_eq!(third.hostname.as_deref(), Some("plxserver01.cyphersoftware.com"));
+    assert_eq!(proxies[0].hostname, None);
+    let anonymity: Vec<_> = proxies.iter().skip(5).take(6).map(|p| p.anonymity).collect();
+    let (a, b) = (Some(Anonymity::Elite), Some(Anonymity::Anonymous));
+    assert_eq!(anonymity, [a, b, a, b, b, b]);
+}
+
+#[test]
+fn cybersyndrome_lists_parse() {
+    let lists: Vec<CybersyndromeList> = ["ranking", "Newest", "jp"].iter().map(|s| s.parse().unwrap()).collect();
+    assert_eq!(
+        lists,
+        [CybersyndromeList::Ranking, CybersyndromeList::Newest, CybersyndromeList::Country(Country::parse("JP"))]
+    );
+    assert!(lists[2].url().ends_with("?q=JP"));
+    assert!("fastest".parse::<CybersyndromeList>().is_err());
+}
+
+#[test]
+fn cybersyndrome_resolves_lists_on_mirror() {
+    // The old single-page setting still reaches every list
+    let legacy = "https://www.cybersyndrome.net/plr6.html";
+    assert_eq!(CybersyndromeList::Newest.url_on(legacy).unwrap(), CybersyndromeList::Newest.url());
+    assert_eq!(
+        CybersyndromeList::Anonymous.url_on("http://127.0.0.1:8080/cs/").unwrap(),
+        "http://127.0.0.1:8080/cs/pla6.html"
+    );
+
+    assert!(Cybersyndrome::new().ranked());
+    let newest = Cybersyndrome::new().with_lists(&[CybersyndromeList::Newest, CybersyndromeList::Anonymous]);
+    assert!(!newest.ranked());
+}
+
+#[test]
+fn cybersyndrome_evaluates_script() {
+    // The modulus is read from the page, not assumed
+    let body = fixture("cybersyndrome.html").replace(")%120;", ")%7;");
+    let proxies = Cybersyndrome::parse(&body).unwrap();
+    assert_eq!(proxies[0].addr.host_port(), "240.182.211.128:80");
+
+    let body = fixture("cybersyndrome.html").replace("+ps[6]+", "+zz[6]+");
+    let err = Cybersyndrome::parse(&body).unwrap_err().to_string();
+    assert!(err.contains("unknown array `zz`"), "{}", err);
+}
+
+#[test]
+fn cybersyndrome_rejects_page_without_script() {
+    assert!(Cybersyndrome::parse("<html><body><table></table></body></html>").is_err());
+}
+
+#[tokio::test]
+async fn replay_serves_recorded_page() {
+    let dir = std::env::temp_dir().join(format!("proxyrs-replay-{}", std::process::id()));
+    let url = "https://api.example.invalid/v2/?request=getproxies";
+    FixtureStore::new(&dir).save(url, &fixture("proxyscrape.txt")).unwrap();
+
+    let options = ProviderOptions::new()
+        .with_url(url)
+        .with_client_mode(ClientMode::Replay(dir.clone()));
+    let mut provider = ProxyScrape::new().with_options(options);
+    let proxies = provider.list().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(proxies.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn url_list_takes_fetch_options() {
+    let dir = std::env::temp_dir().join(format!("proxyrs-url-list-{}", std::process::id()));
+    let url = "https://lists.example.invalid/http.txt";
+    FixtureStore::new(&dir).save(url, &fixture("proxyscrape.txt")).unwrap();
+
+    let section: toml::Value = toml::from_str(&format!(
+        r#"
+        enabled = true
+        url = "{}"
+        headers = {{ Authorization = "Bearer token" }}
+        ttl_secs = 600
+        proxy = "http://127.0.0.1:3128"
+        "#,
+        url
+    ))
+    .unwrap();
+    let mut providers = ProviderRegistry::new().create("url_list", section).unwrap();
+    let provider = &mut providers[0];
+    provider.set_client_mode(ClientMode::Replay(dir.clone()));
+    let proxies = provider.list().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(provider.name(), "lists.example.invalid");
+    assert_eq!(proxies.unwrap().len(), 4);
+
+    let bad_proxy = format!("enabled = true\nurl = \"{}\"\nproxy = \"nonsense\"", url);
+    let section: toml::Value = toml::from_str(&bad_proxy).unwrap();
+    assert!(ProviderRegistry::new().create("url_list", section).is_err());
+}
+
+#[tokio::test]
+async fn url_list_reports_rows() {
+    let dir = std::env::temp_dir().join(format!("proxyrs-url-report-{}", std::process::id()));
+    let url = "https://lists.example.invalid/v1.2/proxies";
+    let body = "1.2.3.4:8080\nnot a proxy\n# comment\n5.6.7.8:3128\n9.9.9.9:0\n";
+    FixtureStore::new(&dir).save(url, body).unwrap();
+
+    let options = ProviderOptions::new().with_client_mode(ClientMode::Replay(dir.clone()));
+    let mut provider = UrlListProvider::new(url).with_options(options);
+    let proxies = provider.list().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(addrs(&proxies.unwrap()), ["1.2.3.4:8080", "5.6.7.8:3128"]);
+    let report = provider.take_report().unwrap();
+    assert_eq!((report.rows, report.accepted, report.rejected_total()), (4, 2, 2));
+    assert_eq!(report.rejected.get("invalid address"), Some(&2));
+}
+
+#[test]
+fn url_list_detects_format_from_last_segment() {
+    let csv = "host,port\n1.2.3.4,8080\n";
+    let proxies = UrlListProvider::new("https://lists.example.invalid/v1/http.csv").parse(csv).unwrap();
+    assert_eq!(addrs(&proxies), ["1.2.3.4:8080"]);
+
+    // A dot in an earlier segment is not an extension
+    let json = r#"["http://1.2.3.4:8080"]"#;
+    let proxies = UrlListProvider::new("https://lists.example.invalid/data.csv/latest").parse(json).unwrap();
+    assert_eq!(addrs(&proxies), ["1.2.3.4:8080"]);
+}
+
+#[test]
+fn fixture_store_keeps_same_second_captures() {
+    let dir = std::env::temp_dir().join(format!("proxyrs-captures-{}", std::process::id()));
+    let store = FixtureStore::new(&dir);
+    let url = "https://api.example.invalid/list";
+    let first = store.save(url, "first").unwrap();
+    let second = store.save(url, "second").unwrap();
+    let latest = store.load(url).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_ne!(first, second);
+    assert_eq!(latest, "second");
+}
+
+#[test]
+fn cybersyndrome_reports_unparsable_rows() {
+    let mut report = ParseReport::new();
+    Cybersyndrome::parse_with_report(&fixture("cybersyndrome.html"), &mut report).unwrap();
+    assert_eq!((report.rows, report.rejected_total()), (30, 0));
+
+    // A mangled octet used to become 0 and yield a bogus address
+    let body = fixture("cybersyndrome.html").replace("var as=[52,", "var as=[5x,");
+    let mut report = ParseReport::new();
+    let proxies = Cybersyndrome::parse_with_report(&body, &mut report).unwrap();
+    assert_eq!(proxies.len(), 29);
+    assert_eq!(report.rejected.get("unparsable octet"), Some(&1));
+}
+
+#[test]
+fn free_proxy_list_warns_about_fallback_table() {
+    let body = fixture("free_proxy_list.html").replace("<section id=\"list\">", "<section>");
+    let mut report = ParseReport::new();
+    let proxies = FreeProxyList::parse_with_report(&body, &mut report).unwrap();
+    assert_eq!(proxies.len(), 5);
+    assert_eq!(report.warnings.len(), 1);
+}
+
+#[test]
+fn socks4_upstream_is_dropped() {
+    let mut base = BaseProvider::new();
+    let addr = ProxyAddr::new("192.0.2.10".parse().unwrap(), 1080);
+    base.proxy_upstream = Some(Proxy::new(addr, ProxyType::Socks4, Country::UNKNOWN));
+
+    // reqwest can't use SOCKS4, so the client goes direct from now on
+    assert!(base.client().is_ok());
+    assert!(base.proxy_upstream.is_none());
+}
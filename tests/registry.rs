@@ -0,0 +1,136 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use proxyrs::configuration::Settings;
+use proxyrs::provider::Provider;
+use proxyrs::providers::registry::ProviderRegistry;
+use proxyrs::proxy::{Proxy, ProxyMetadata};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct StaticConfig {
+    label: String,
+}
+
+struct StaticProvider(String);
+
+#[async_trait]
+impl Provider for StaticProvider {
+    async fn list(&mut self) -> Result<Vec<ProxyMetadata>> {
+        Ok(Vec::new())
+    }
+
+    fn name(&self) -> &str {
+        &self.0
+    }
+
+    fn set_proxy(&mut self, _proxy: Proxy) {}
+}
+
+const SETTINGS: &str = r#"
+[providers.cool_proxy]
+enabled = false
+
+[providers.free_proxy_list]
+enabled = true
+ttl_secs = 60
+
+[providers.cybersyndrome]
+enabled = true
+lists = ["newest", "JP"]
+
+[[providers.url_list]]
+enabled = true
+url = "https://raw.example.invalid/list.txt"
+
+[[providers.url_list]]
+enabled = false
+url = "https://raw.example.invalid/off.txt"
+
+[[providers.url_list]]
+enabled = true
+name = "api"
+url = "https://api.example.invalid/proxies"
+format = "json"
+
+[[providers.scrape]]
+enabled = true
+name = "table-site"
+url = "https://table.example.invalid/"
+rows = "tr"
+host = { column = 0 }
+
+[[providers.scrape]]
+enabled = true
+name = "broken"
+url = "https://broken.example.invalid/"
+rows = "tr["
+host = { column = 0 }
+
+[providers.static]
+enabled = true
+label = "custom"
+
+[providers.nobody]
+enabled = true
+"#;
+
+fn names(providers: &[Box<dyn Provider>]) -> Vec<String> {
+    let mut names: Vec<_> = providers.iter().map(|p| p.name().to_string()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn builds_enabled_sections() {
+    let settings: Settings = toml::from_str(SETTINGS).unwrap();
+    let mut registry = ProviderRegistry::new();
+    registry.register("static", |section| {
+        let conf: StaticConfig = section.try_into()?;
+        Ok(Box::new(StaticProvider(conf.label)))
+    });
+
+    // Disabled entries, the unregistered section and the bad selector are skipped
+    assert_eq!(
+        names(&registry.build(&settings)),
+        [
+            "api",
+            "custom",
+            "free-proxy-list.net",
+            "raw.example.invalid",
+            "table-site",
+            "www.cybersyndrome.net",
+        ]
+    );
+
+    let cybersyndrome = registry.build(&settings).into_iter().find(|p| p.name() == "www.cybersyndrome.net");
+    assert!(!cybersyndrome.unwrap().ranked(), "no ranking list configured");
+}
+
+#[test]
+fn custom_sections_need_a_factory() {
+    let settings: Settings = toml::from_str(SETTINGS).unwrap();
+    let built = names(&ProviderRegistry::new().build(&settings));
+    assert!(!built.contains(&"custom".to_string()));
+
+    let mut registry = ProviderRegistry::new();
+    registry.unregister("free_proxy_list");
+    assert!(!registry.contains("free_proxy_list"));
+    assert!(!names(&registry.build(&settings)).contains(&"free-proxy-list.net".to_string()));
+    assert!(registry.create("free_proxy_list", toml::Value::Table(Default::default())).is_err());
+}
+
+#[test]
+fn default_settings_build_every_list_site() {
+    assert_eq!(
+        names(&ProviderRegistry::new().build(&Settings::default())),
+        [
+            "api.proxyscrape.com",
+            "free-proxy-list.net",
+            "socks-proxy.net",
+            "sslproxies.org",
+            "us-proxy.org",
+            "www.cool-proxy.net",
+            "www.cybersyndrome.net",
+        ]
+    );
+}
@@ -0,0 +1,55 @@
+use proxyrs::country::Country;
+use proxyrs::proxy::{ProxyMetadata, ProxyType};
+use proxyrs::proxy_addr::ProxyAddr;
+use proxyrs::sampling::{AddrState, SampleOrder, SamplingPolicy};
+
+fn list(countries: &[&str]) -> Vec<ProxyMetadata> {
+    countries
+        .iter()
+        .enumerate()
+        .map(|(i, country)| {
+            let addr = ProxyAddr::new("1.2.3.4".parse().unwrap(), 1000 + i as u16);
+            ProxyMetadata::new(addr, ProxyType::Http, Country::parse(country))
+        })
+        .collect()
+}
+
+fn ports(sample: &[ProxyMetadata]) -> Vec<u16> {
+    sample.iter().map(|m| m.addr.port).collect()
+}
+
+#[test]
+fn unseen_first_then_good_skipping_bad_and_pending() {
+    use AddrState::*;
+    let policy = SamplingPolicy::new().with_order(SampleOrder::ProviderOrder);
+    let states = [Good, Bad, Unseen, Pending, Unseen, Good];
+
+    let sample = policy.sample(list(&["US"; 6]), &states, false, &[]);
+    assert_eq!(ports(&sample), [1002, 1004, 1000, 1005]);
+
+    let sample = policy.with_batch_size(3).sample(list(&["US"; 6]), &states, false, &[]);
+    assert_eq!(ports(&sample), [1002, 1004, 1000]);
+}
+
+#[test]
+fn auto_keeps_order_of_ranked_lists_only() {
+    let policy = SamplingPolicy::new().with_batch_size(50);
+    let states = [AddrState::Unseen; 50];
+
+    let sample = policy.sample(list(&["US"; 50]), &states, true, &[]);
+    assert_eq!(ports(&sample), (1000..1050).collect::<Vec<_>>());
+
+    // 50 entries coming back in list order by chance is out of the question
+    let sample = policy.sample(list(&["US"; 50]), &states, false, &[]);
+    assert_eq!(sample.len(), 50);
+    assert_ne!(ports(&sample), (1000..1050).collect::<Vec<_>>());
+}
+
+#[test]
+fn preferred_countries_go_first() {
+    let policy = SamplingPolicy::new().with_order(SampleOrder::ProviderOrder).with_batch_size(2);
+    let states = [AddrState::Unseen; 4];
+
+    let sample = policy.sample(list(&["US", "DE", "US", "JP"]), &states, false, &[Country::parse("JP")]);
+    assert_eq!(ports(&sample), [1003, 1000]);
+}
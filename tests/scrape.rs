@@ -0,0 +1,179 @@
+use proxyrs::country::Country;
+use proxyrs::provider::Provider;
+use proxyrs::providers::fixtures::{ClientMode, FixtureStore};
+use proxyrs::providers::scrape::{ScrapeConfig, ScrapeProvider};
+use proxyrs::proxy::{ProxyMetadata, ProxyType};
+use std::path::PathBuf;
+
+fn config(extra: &str) -> ScrapeConfig {
+    let toml = format!(
+        r#"
+        enabled = true
+        name = "example"
+        url = "https://list.example.invalid/proxies?page={{page}}"
+        rows = "table#list tbody tr"
+        host = {{ column = 0 }}
+        port = {{ column = 1, regex = "(\\d+)" }}
+        kind = {{ column = 2, map = {{ "Socks 5" = "socks5", "HTTPS proxy" = "https" }} }}
+        country = {{ column = 3, selector = "img", attr = "alt" }}
+        {}
+        "#,
+        extra
+    );
+    toml::from_str(&toml).unwrap()
+}
+
+fn page(rows: &[(&str, &str, &str, &str)], next: Option<&str>) -> String {
+    let rows: String = rows
+        .iter()
+        .map(|(host, port, kind, country)| {
+            format!(
+                "<tr><td>{}</td><td>:{}</td><td>{}</td><td><img src='f.png' alt='{}'></td></tr>",
+                host, port, kind, country
+            )
+        })
+        .collect();
+    let next = next.map(|href| format!("<a class='next' href='{}'>Next</a>", href)).unwrap_or_default();
+    format!(
+        "<html><body><table id='list'><thead><tr><th>IP</th></tr></thead><tbody>{}</tbody></table>{}</body></html>",
+        rows, next
+    )
+}
+
+fn entries(proxies: &[ProxyMetadata]) -> Vec<(String, ProxyType, Country)> {
+    proxies.iter().map(|m| (m.addr.host_port(), m.kind.clone(), m.country)).collect()
+}
+
+fn store(name: &str, pages: &[(&str, String)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("proxyrs-scrape-{}-{}", name, std::process::id()));
+    let store = FixtureStore::new(&dir);
+    for (url, body) in pages {
+        store.save(url, body).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn parse_applies_field_maps() {
+    let provider = ScrapeProvider::new(config("")).unwrap();
+    let body = page(
+        &[
+            ("1.2.3.4", "8080", "Socks 5", "Germany"),
+            ("5.6.7.8", "3128", "https proxy", "JP"),
+            ("9.9.9.9", "80", "carrier pigeon", ""),
+            ("", "80", "http", "US"),
+            ("10.0.0.1", "none", "http", "US"),
+        ],
+        None,
+    );
+
+    assert_eq!(
+        entries(&provider.parse(&body)),
+        [
+            ("1.2.3.4:8080".to_string(), ProxyType::Socks5, Country::parse("DE")),
+            ("5.6.7.8:3128".to_string(), ProxyType::Https, Country::parse("JP")),
+            ("9.9.9.9:80".to_string(), ProxyType::Http, Country::UNKNOWN),
+        ]
+    );
+}
+
+#[test]
+fn whole_address_in_one_field() {
+    let mut config = config("default_kind = \"socks4\"");
+    config.port = None;
+    config.kind = None;
+    config.host.regex = Some(r"(\S+:\d+)".into());
+    let provider = ScrapeProvider::new(config).unwrap();
+
+    let body = page(&[("socks5://1.2.3.4:1080", "", "", ""), ("proxy 5.6.7.8:3128 (fast)", "", "", "")], None);
+    let kinds: Vec<_> = entries(&provider.parse(&body)).into_iter().map(|(addr, kind, _)| (addr, kind)).collect();
+    assert_eq!(
+        kinds,
+        [("1.2.3.4:1080".to_string(), ProxyType::Socks5), ("5.6.7.8:3128".to_string(), ProxyType::Socks4)]
+    );
+}
+
+#[test]
+fn invalid_config_fails_up_front() {
+    let mut bad_selector = config("");
+    bad_selector.rows = "tr[".into();
+    assert!(ScrapeProvider::new(bad_selector).is_err());
+
+    let mut bad_regex = config("");
+    bad_regex.host.regex = Some("(".into());
+    assert!(ScrapeProvider::new(bad_regex).is_err());
+}
+
+#[tokio::test]
+async fn follows_page_numbers_until_an_empty_page() {
+    let url = |n: usize| format!("https://list.example.invalid/proxies?page={}", n);
+    let dir = store(
+        "numbers",
+        &[
+            (&url(2), page(&[("1.1.1.1", "80", "http", "US")], None)),
+            (&url(3), page(&[("2.2.2.2", "80", "http", "US"), ("3.3.3.3", "80", "http", "US")], None)),
+            (&url(4), page(&[], None)),
+        ],
+    );
+    let mut provider = ScrapeProvider::new(config("pagination = { start = 2, max_pages = 10 }")).unwrap();
+    provider.set_client_mode(ClientMode::Replay(dir.clone()));
+    let proxies = provider.list().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(proxies.unwrap().len(), 3);
+    let report = provider.take_report().unwrap();
+    assert_eq!((report.rows, report.accepted), (3, 3));
+}
+
+#[tokio::test]
+async fn follows_next_links_up_to_max_pages() {
+    let first = "https://list.example.invalid/proxies";
+    let second = "https://list.example.invalid/proxies/more";
+    let dir = store(
+        "next",
+        &[
+            (first, page(&[("1.1.1.1", "80", "http", "US")], Some("/proxies/more"))),
+            (second, page(&[("2.2.2.2", "80", "http", "US")], Some("/proxies/even-more"))),
+        ],
+    );
+    let mut config = config("pagination = { next = \"a.next\", max_pages = 2 }");
+    config.url = first.into();
+    let mut provider = ScrapeProvider::new(config).unwrap();
+    provider.set_client_mode(ClientMode::Replay(dir.clone()));
+    let proxies = provider.list().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let addrs: Vec<_> = entries(&proxies.unwrap()).into_iter().map(|(addr, ..)| addr).collect();
+    assert_eq!(addrs, ["1.1.1.1:80", "2.2.2.2:80"]);
+}
+
+#[tokio::test]
+async fn failed_later_page_keeps_earlier_ones() {
+    let url = |n: usize| format!("https://list.example.invalid/proxies?page={}", n);
+    // Nothing recorded for page 3, so replaying it fails like a dead link would
+    let dir = store(
+        "failed",
+        &[
+            (&url(1), page(&[("1.1.1.1", "80", "http", "US")], None)),
+            (&url(2), page(&[("2.2.2.2", "80", "http", "US")], None)),
+        ],
+    );
+    let mut provider = ScrapeProvider::new(config("pagination = { max_pages = 5 }")).unwrap();
+    provider.set_client_mode(ClientMode::Replay(dir.clone()));
+    let proxies = provider.list().await;
+
+    assert_eq!(proxies.unwrap().len(), 2);
+    let report = provider.take_report().unwrap();
+    assert_eq!(report.accepted, 2);
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].starts_with(&url(3)), "{}", report.warnings[0]);
+
+    // Without page 1 there is nothing to return, but the failure is still reported
+    let mut provider = ScrapeProvider::new(config("pagination = { start = 3, max_pages = 5 }")).unwrap();
+    provider.set_client_mode(ClientMode::Replay(dir.clone()));
+    let result = provider.list().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.is_err());
+    assert!(provider.take_report().unwrap().error.is_some());
+}